@@ -0,0 +1,82 @@
+//! The current date, for stamping saved runs.
+//! Uses the system clock on native builds and the browser's clock on the web.
+
+use bevy::utils::SystemTime;
+
+/// A day on the calendar, in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        Self::from_days_since_epoch((seconds / 86_400) as i64)
+    }
+
+    /// Converts a count of days since 1970-01-01.
+    /// See <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+    fn from_days_since_epoch(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (year_of_era + era * 400 + i64::from(month <= 2)) as i32;
+        Self { year, month, day }
+    }
+}
+
+impl std::fmt::Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> Date {
+        Date { year, month, day }
+    }
+
+    #[test]
+    fn converts_days_since_epoch() {
+        assert_eq!(Date::from_days_since_epoch(0), date(1970, 1, 1));
+        assert_eq!(Date::from_days_since_epoch(59), date(1970, 3, 1));
+        assert_eq!(Date::from_days_since_epoch(-1), date(1969, 12, 31));
+    }
+
+    #[test]
+    fn crosses_year_boundaries() {
+        assert_eq!(Date::from_days_since_epoch(10_956), date(1999, 12, 31));
+        assert_eq!(Date::from_days_since_epoch(10_957), date(2000, 1, 1));
+        assert_eq!(Date::from_days_since_epoch(19_722), date(2023, 12, 31));
+        assert_eq!(Date::from_days_since_epoch(19_723), date(2024, 1, 1));
+    }
+
+    #[test]
+    fn handles_leap_years() {
+        // 2000 is a leap year for being divisible by 400, 2100 isn't for being divisible by 100.
+        assert_eq!(Date::from_days_since_epoch(11_016), date(2000, 2, 29));
+        assert_eq!(Date::from_days_since_epoch(11_017), date(2000, 3, 1));
+        assert_eq!(Date::from_days_since_epoch(19_782), date(2024, 2, 29));
+        assert_eq!(Date::from_days_since_epoch(47_540), date(2100, 2, 28));
+        assert_eq!(Date::from_days_since_epoch(47_541), date(2100, 3, 1));
+    }
+
+    #[test]
+    fn displays_as_iso_date() {
+        assert_eq!(date(2024, 2, 9).to_string(), "2024-02-09");
+    }
+}
@@ -0,0 +1,92 @@
+//! Copy text to the system clipboard.
+//! Native builds go through `arboard`, web builds through the browser's clipboard API.
+
+use std::sync::{
+    atomic::{AtomicU8, Ordering},
+    Arc,
+};
+
+use bevy::log::warn;
+
+const PENDING: u8 = 0;
+const COPIED: u8 = 1;
+const FAILED: u8 = 2;
+
+/// How a copy to the clipboard went. On the web the browser answers asynchronously,
+/// so the outcome stays unknown for a little while.
+#[derive(Debug, Clone, Default)]
+pub struct CopyStatus(Arc<AtomicU8>);
+
+impl CopyStatus {
+    /// Whether the text made it onto the clipboard, or `None` while that isn't known yet.
+    pub fn result(&self) -> Option<bool> {
+        match self.0.load(Ordering::Relaxed) {
+            PENDING => None,
+            state => Some(state == COPIED),
+        }
+    }
+
+    fn finish(&self, copied: bool) {
+        if !copied {
+            warn!("Could not copy text to the clipboard");
+        }
+        self.0
+            .store(if copied { COPIED } else { FAILED }, Ordering::Relaxed);
+    }
+}
+
+/// Replace the clipboard contents with `text`. Browsers only allow this in response
+/// to the player's input, like a button press.
+pub fn copy(text: &str) -> CopyStatus {
+    let status = CopyStatus::default();
+    write(text, &status);
+    status
+}
+
+/// Some platforms only serve the clipboard contents while the clipboard is alive,
+/// so it is kept around once opened.
+#[cfg(not(target_family = "wasm"))]
+static CLIPBOARD: std::sync::Mutex<Option<arboard::Clipboard>> = std::sync::Mutex::new(None);
+
+#[cfg(not(target_family = "wasm"))]
+fn write(text: &str, status: &CopyStatus) {
+    let Ok(mut clipboard) = CLIPBOARD.lock() else {
+        status.finish(false);
+        return;
+    };
+    if clipboard.is_none() {
+        *clipboard = arboard::Clipboard::new().ok();
+    }
+    status.finish(
+        clipboard
+            .as_mut()
+            .is_some_and(|clipboard| clipboard.set_text(text).is_ok()),
+    );
+}
+
+#[cfg(target_family = "wasm")]
+fn write(text: &str, status: &CopyStatus) {
+    use web_sys::wasm_bindgen::{closure::Closure, JsValue};
+
+    let Some(window) = web_sys::window() else {
+        status.finish(false);
+        return;
+    };
+
+    let on_copied = {
+        let status = status.clone();
+        Closure::once(move |_: JsValue| status.finish(true))
+    };
+    let on_failed = {
+        let status = status.clone();
+        Closure::once(move |_: JsValue| status.finish(false))
+    };
+    let _ = window
+        .navigator()
+        .clipboard()
+        .write_text(text)
+        .then2(&on_copied, &on_failed);
+    // The browser calls one of these later, so they have to outlive this function.
+    on_copied.forget();
+    on_failed.forget();
+}
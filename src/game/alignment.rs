@@ -0,0 +1,457 @@
+//! Alignments between satellites. Satellites at nearly the same angle around the sun are in
+//! conjunction, satellites on opposite sides of it are in opposition. Levels can attach bonuses
+//! that last while an alignment does, and the forecast overlay shows the ones coming up.
+
+use std::f32::consts::{PI, TAU};
+
+use bevy::prelude::*;
+use bevy_vector_shapes::{painter::ShapePainter, shapes::LinePainter};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    assets::{HandleMap, LevelKey, SfxKey},
+    audio::sfx::PlaySfx,
+    campaign::CurrentLevel,
+    ledger::{Ledger, OrderFulfilled},
+    market::MarketPrices,
+    palette::GamePalette,
+    research::ResearchModifiers,
+    spawn::{
+        connection::{
+            check_for_invalid_connections, ConnectionAnchor, ConnectionConfig,
+            ConnectionProperties, ConnectionTarget, LinkKind,
+        },
+        level::Level,
+        planet::{OrbitalMovement, OrbitalPosition, Planet},
+    },
+};
+use crate::{
+    screen::Screen,
+    settings::{BindingAction, Settings},
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Alignments>();
+    app.init_resource::<Forecast>();
+
+    app.add_systems(OnEnter(Screen::Playing), reset_alignments);
+    app.observe(announce_alignment);
+    app.observe(remember_ended_alignment);
+    app.observe(pay_trade_bonus);
+    app.add_systems(
+        Update,
+        toggle_forecast
+            .in_set(AppSet::RecordInput)
+            .run_if(in_state(Screen::Playing)),
+    );
+    // Boosted ranges have to be in place before connections are checked against them.
+    app.add_systems(
+        Update,
+        (detect_alignments, apply_range_boosts)
+            .chain()
+            .in_set(AppSet::PrepareUpdate)
+            .before(check_for_invalid_connections)
+            .run_if(in_state(Screen::Playing)),
+    );
+    app.add_systems(
+        Update,
+        forecast_alignments
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing).and_then(forecast_visible)),
+    );
+    app.add_systems(
+        Update,
+        draw_alignments
+            .in_set(AppSet::Render)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// How far apart in angle, in radians, satellites can be and still count as aligned.
+const ALIGNMENT_TOLERANCE: f32 = 0.08;
+
+/// How far ahead the forecast looks, in seconds of game time.
+const FORECAST_HORIZON_SECS: f32 = 60.0;
+
+/// The most alignments the forecast lists.
+const FORECAST_ENTRIES: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlignmentKind {
+    /// Two or more satellites at the same angle around the sun.
+    Conjunction,
+    /// Two satellites on opposite sides of the sun.
+    Opposition,
+}
+
+impl AlignmentKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AlignmentKind::Conjunction => "Conjunction",
+            AlignmentKind::Opposition => "Opposition",
+        }
+    }
+
+    /// The angle between satellites in this kind of alignment.
+    fn separation(&self) -> f32 {
+        match self {
+            AlignmentKind::Conjunction => 0.0,
+            AlignmentKind::Opposition => PI,
+        }
+    }
+}
+
+/// A bonus a level grants while satellites are aligned, from its `alignments` entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlignmentEffect {
+    pub kind: AlignmentKind,
+    pub bonus: AlignmentBonus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AlignmentBonus {
+    /// Deliveries to an aligned satellite pay this fraction of the usual payout on top.
+    Trade(f32),
+    /// Connections between aligned satellites reach this fraction further.
+    Range(f32),
+}
+
+impl AlignmentBonus {
+    pub fn label(&self) -> String {
+        match self {
+            AlignmentBonus::Trade(bonus) => format!("+{:.0}% trade", bonus * 100.0),
+            AlignmentBonus::Range(bonus) => format!("+{:.0}% range", bonus * 100.0),
+        }
+    }
+}
+
+/// Satellites that are lined up right now.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alignment {
+    pub kind: AlignmentKind,
+    /// Sorted, so the same alignment compares equal from frame to frame.
+    pub satellites: Vec<Entity>,
+}
+
+/// Every alignment under way.
+#[derive(Resource, Debug, Default)]
+pub struct Alignments(pub Vec<Alignment>);
+
+/// Triggered when satellites line up.
+#[derive(Event, Debug)]
+pub struct AlignmentStarted(pub Alignment);
+
+/// Triggered when satellites drift out of line.
+#[derive(Event, Debug)]
+pub struct AlignmentEnded(pub Alignment);
+
+/// The alignments coming up, while the forecast overlay is shown.
+#[derive(Resource, Debug, Default)]
+pub struct Forecast {
+    pub visible: bool,
+    /// Soonest first.
+    pub upcoming: Vec<ForecastEntry>,
+    /// The alignment that ended most recently.
+    pub last_ended: Option<Alignment>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ForecastEntry {
+    pub kind: AlignmentKind,
+    pub satellites: (Entity, Entity),
+    /// Seconds of game time until the satellites line up.
+    pub seconds: f32,
+}
+
+pub fn forecast_visible(forecast: Res<Forecast>) -> bool {
+    forecast.visible
+}
+
+/// The bonuses the current level grants for this kind of alignment.
+pub fn level_bonuses<'a>(
+    level: Option<&'a Level>,
+    kind: AlignmentKind,
+) -> impl Iterator<Item = AlignmentBonus> + 'a {
+    level
+        .into_iter()
+        .flat_map(|level| &level.alignments)
+        .filter(move |effect| effect.kind == kind)
+        .map(|effect| effect.bonus)
+}
+
+/// The angle between two positions around the sun, between 0 and PI.
+fn angle_between(a: f32, b: f32) -> f32 {
+    let difference = (a - b).rem_euclid(TAU);
+    difference.min(TAU - difference)
+}
+
+fn reset_alignments(mut alignments: ResMut<Alignments>, mut forecast: ResMut<Forecast>) {
+    alignments.0.clear();
+    forecast.upcoming.clear();
+    forecast.last_ended = None;
+}
+
+fn toggle_forecast(
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    mut forecast: ResMut<Forecast>,
+) {
+    if input.just_pressed(settings.key_bindings.get(BindingAction::ToggleForecast)) {
+        forecast.visible = !forecast.visible;
+    }
+}
+
+fn detect_alignments(
+    mut commands: Commands,
+    mut alignments: ResMut<Alignments>,
+    satellite_query: Query<(Entity, &OrbitalPosition), With<Planet>>,
+) {
+    let mut angles: Vec<_> = satellite_query
+        .iter()
+        .map(|(entity, orbit)| (orbit.position.rem_euclid(TAU), entity))
+        .collect();
+    angles.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    // Satellites next to each other in angle form a conjunction as long as every gap is small.
+    let mut groups: Vec<Vec<(f32, Entity)>> = Vec::new();
+    for &(angle, entity) in &angles {
+        match groups.last_mut() {
+            Some(group) if angle - group[group.len() - 1].0 < ALIGNMENT_TOLERANCE => {
+                group.push((angle, entity));
+            }
+            _ => groups.push(vec![(angle, entity)]),
+        }
+    }
+    // The first and last groups meet where the angle wraps around.
+    if groups.len() > 1 {
+        let first = groups[0][0].0;
+        let last = &groups[groups.len() - 1];
+        if first + TAU - last[last.len() - 1].0 < ALIGNMENT_TOLERANCE {
+            let wrapped = groups.remove(0);
+            groups.last_mut().unwrap().extend(wrapped);
+        }
+    }
+
+    let mut current: Vec<_> = groups
+        .into_iter()
+        .filter(|group| group.len() > 1)
+        .map(|group| Alignment {
+            kind: AlignmentKind::Conjunction,
+            satellites: group.into_iter().map(|(_, entity)| entity).collect(),
+        })
+        .collect();
+
+    for (i, &(a, first)) in angles.iter().enumerate() {
+        for &(b, second) in &angles[i + 1..] {
+            if PI - angle_between(a, b) < ALIGNMENT_TOLERANCE {
+                current.push(Alignment {
+                    kind: AlignmentKind::Opposition,
+                    satellites: vec![first, second],
+                });
+            }
+        }
+    }
+    for alignment in &mut current {
+        alignment.satellites.sort();
+    }
+
+    if current == alignments.0 {
+        return;
+    }
+    for alignment in &alignments.0 {
+        if !current.contains(alignment) {
+            commands.trigger(AlignmentEnded(alignment.clone()));
+        }
+    }
+    for alignment in &current {
+        if !alignments.0.contains(alignment) {
+            commands.trigger(AlignmentStarted(alignment.clone()));
+        }
+    }
+    alignments.0 = current;
+}
+
+/// Alignments that come with a bonus are worth pointing out.
+fn announce_alignment(
+    trigger: Trigger<AlignmentStarted>,
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    level_handles: Res<HandleMap<LevelKey>>,
+    levels: Res<Assets<Level>>,
+    satellite_query: Query<&OrbitalPosition>,
+) {
+    let alignment = &trigger.event().0;
+    let level = levels.get(&level_handles[&current_level.0]);
+    if level_bonuses(level, alignment.kind).next().is_none() {
+        return;
+    }
+
+    let Some(Ok(orbit)) = alignment
+        .satellites
+        .first()
+        .map(|e| satellite_query.get(*e))
+    else {
+        return;
+    };
+    commands.trigger(PlaySfx::At(
+        SfxKey::Alignment,
+        orbit.get_euclidean_position(),
+    ));
+}
+
+fn remember_ended_alignment(trigger: Trigger<AlignmentEnded>, mut forecast: ResMut<Forecast>) {
+    forecast.last_ended = Some(trigger.event().0.clone());
+}
+
+/// A connection reaches as far as research and its kind of link allow, plus the boost of
+/// any alignment between its two ends.
+fn apply_range_boosts(
+    current_level: Res<CurrentLevel>,
+    level_handles: Res<HandleMap<LevelKey>>,
+    levels: Res<Assets<Level>>,
+    alignments: Res<Alignments>,
+    connection_config: Res<ConnectionConfig>,
+    modifiers: Res<ResearchModifiers>,
+    mut connection_query: Query<(
+        &ConnectionAnchor,
+        &ConnectionTarget,
+        &LinkKind,
+        &mut ConnectionProperties,
+    )>,
+) {
+    let level = levels.get(&level_handles[&current_level.0]);
+
+    for (anchor, target, kind, mut properties) in &mut connection_query {
+        let boost: f32 = match target {
+            ConnectionTarget::Satellite(target) => alignments
+                .0
+                .iter()
+                .filter(|alignment| {
+                    alignment.satellites.contains(&anchor.satellite)
+                        && alignment.satellites.contains(target)
+                })
+                .flat_map(|alignment| level_bonuses(level, alignment.kind))
+                .map(|bonus| match bonus {
+                    AlignmentBonus::Range(boost) => boost,
+                    AlignmentBonus::Trade(_) => 0.0,
+                })
+                .sum(),
+            // A connection still being built isn't aligned with anything.
+            ConnectionTarget::Position(_) => 0.0,
+        };
+
+        let range = modifiers.connection_range(&connection_config, *kind) * (1.0 + boost);
+        if properties.range != range {
+            properties.range = range;
+        }
+    }
+}
+
+fn pay_trade_bonus(
+    trigger: Trigger<OrderFulfilled>,
+    current_level: Res<CurrentLevel>,
+    level_handles: Res<HandleMap<LevelKey>>,
+    levels: Res<Assets<Level>>,
+    alignments: Res<Alignments>,
+    mut ledger: ResMut<Ledger>,
+    market_query: Query<&MarketPrices>,
+) {
+    let level = levels.get(&level_handles[&current_level.0]);
+    let satellite = trigger.event().satellite;
+
+    let bonus: f32 = alignments
+        .0
+        .iter()
+        .filter(|alignment| alignment.satellites.contains(&satellite))
+        .flat_map(|alignment| level_bonuses(level, alignment.kind))
+        .map(|bonus| match bonus {
+            AlignmentBonus::Trade(bonus) => bonus,
+            AlignmentBonus::Range(_) => 0.0,
+        })
+        .sum();
+
+    let payout = (trigger.event().payout(&market_query) as f32 * bonus).round() as i32;
+    if payout > 0 {
+        ledger.money += payout;
+        ledger.score += payout as u32;
+    }
+}
+
+fn forecast_alignments(
+    mut forecast: ResMut<Forecast>,
+    satellite_query: Query<(Entity, &OrbitalPosition, &OrbitalMovement), With<Planet>>,
+) {
+    let satellites: Vec<_> = satellite_query.iter().collect();
+
+    let mut upcoming = Vec::new();
+    for (i, &(first, first_orbit, first_movement)) in satellites.iter().enumerate() {
+        for &(second, second_orbit, second_movement) in &satellites[i + 1..] {
+            let relative_speed = second_movement.speed - first_movement.speed;
+            if relative_speed.abs() < f32::EPSILON {
+                continue;
+            }
+            let relative_angle = second_orbit.position - first_orbit.position;
+
+            for kind in [AlignmentKind::Conjunction, AlignmentKind::Opposition] {
+                // Alignments under way are listed separately.
+                if angle_between(relative_angle, kind.separation()) < ALIGNMENT_TOLERANCE {
+                    continue;
+                }
+                // How far the relative angle has to turn, in the direction it is turning.
+                let turn = ((kind.separation() - relative_angle) * relative_speed.signum())
+                    .rem_euclid(TAU);
+                let seconds = turn / relative_speed.abs();
+                if seconds <= FORECAST_HORIZON_SECS {
+                    upcoming.push(ForecastEntry {
+                        kind,
+                        satellites: (first, second),
+                        seconds,
+                    });
+                }
+            }
+        }
+    }
+    upcoming.sort_by(|a, b| a.seconds.total_cmp(&b.seconds));
+    upcoming.truncate(FORECAST_ENTRIES);
+    forecast.upcoming = upcoming;
+}
+
+fn draw_alignments(
+    mut painter: ShapePainter,
+    palette: Res<GamePalette>,
+    alignments: Res<Alignments>,
+    forecast: Res<Forecast>,
+    satellite_query: Query<(&OrbitalPosition, &OrbitalMovement)>,
+) {
+    // Lines through the sun show which satellites are aligned.
+    painter.thickness = 1.0;
+    painter.set_color(palette.range_guide.with_alpha(0.6));
+    for alignment in &alignments.0 {
+        for satellite in &alignment.satellites {
+            if let Ok((orbit, _)) = satellite_query.get(*satellite) {
+                painter.line(Vec3::ZERO, orbit.get_euclidean_position());
+            }
+        }
+    }
+
+    if !forecast.visible {
+        return;
+    }
+
+    // Ghost lines show where upcoming alignments will happen.
+    painter.thickness = 0.5;
+    painter.set_color(palette.range_guide.with_alpha(0.3));
+    for entry in &forecast.upcoming {
+        for satellite in [entry.satellites.0, entry.satellites.1] {
+            let Ok((orbit, movement)) = satellite_query.get(satellite) else {
+                continue;
+            };
+            let future = OrbitalPosition {
+                radius: orbit.radius,
+                position: orbit.position + movement.speed * entry.seconds,
+            };
+            painter.line(Vec3::ZERO, future.get_euclidean_position());
+        }
+    }
+}
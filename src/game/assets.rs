@@ -1,10 +1,16 @@
 use bevy::{
+    asset::LoadState,
     prelude::*,
     render::texture::{ImageLoaderSettings, ImageSampler},
     utils::HashMap,
 };
+use serde::{Deserialize, Serialize};
+
+use super::spawn::level::Level;
 
 pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(AssetManifest::parse());
+
     app.register_type::<HandleMap<ImageKey>>();
     app.init_resource::<HandleMap<ImageKey>>();
 
@@ -13,34 +19,71 @@ pub(super) fn plugin(app: &mut App) {
 
     app.register_type::<HandleMap<SoundtrackKey>>();
     app.init_resource::<HandleMap<SoundtrackKey>>();
+
+    app.register_type::<HandleMap<LevelKey>>();
+    app.init_resource::<HandleMap<LevelKey>>();
+}
+
+/// Lists every asset the game loads up front. See `assets/manifest.ron`.
+#[derive(Resource, Deserialize)]
+pub struct AssetManifest {
+    images: HashMap<ImageKey, ImageEntry>,
+    sfx: HashMap<SfxKey, String>,
+    soundtracks: HashMap<SoundtrackKey, String>,
+    levels: HashMap<LevelKey, String>,
+}
+
+#[derive(Deserialize)]
+struct ImageEntry {
+    path: String,
+    /// Sample with nearest neighbour filtering so pixels stay crisp.
+    #[serde(default)]
+    pixel_art: bool,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect)]
+impl AssetManifest {
+    /// The manifest is built into the binary so it is available before anything loads.
+    fn parse() -> Self {
+        ron::from_str(include_str!("../../assets/manifest.ron"))
+            .unwrap_or_else(|error| panic!("invalid asset manifest: {error}"))
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect, Deserialize, Debug)]
 pub enum ImageKey {
     Ducky,
 }
 
 impl AssetKey for ImageKey {
     type Asset = Image;
+    const ALL: &'static [Self] = &[ImageKey::Ducky];
 }
 
 impl FromWorld for HandleMap<ImageKey> {
     fn from_world(world: &mut World) -> Self {
         let asset_server = world.resource::<AssetServer>();
-        [(
-            ImageKey::Ducky,
-            asset_server.load_with_settings(
-                "images/ducky.png",
-                |settings: &mut ImageLoaderSettings| {
-                    settings.sampler = ImageSampler::nearest();
-                },
-            ),
-        )]
-        .into()
+        let manifest = world.resource::<AssetManifest>();
+        manifest
+            .images
+            .iter()
+            .map(|(key, entry)| {
+                let pixel_art = entry.pixel_art;
+                let handle = asset_server.load_with_settings(
+                    entry.path.clone(),
+                    move |settings: &mut ImageLoaderSettings| {
+                        if pixel_art {
+                            settings.sampler = ImageSampler::nearest();
+                        }
+                    },
+                );
+                (*key, handle)
+            })
+            .collect::<HashMap<_, _>>()
+            .into()
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect, Deserialize, Debug)]
 pub enum SfxKey {
     ButtonHover,
     ButtonPress,
@@ -48,62 +91,103 @@ pub enum SfxKey {
     Step2,
     Step3,
     Step4,
+    Alert,
+    Delivery,
+    LinkBreak,
+    Departure,
+    Alignment,
 }
 
 impl AssetKey for SfxKey {
     type Asset = AudioSource;
+    const ALL: &'static [Self] = &[
+        SfxKey::ButtonHover,
+        SfxKey::ButtonPress,
+        SfxKey::Step1,
+        SfxKey::Step2,
+        SfxKey::Step3,
+        SfxKey::Step4,
+        SfxKey::Alert,
+        SfxKey::Delivery,
+        SfxKey::LinkBreak,
+        SfxKey::Departure,
+        SfxKey::Alignment,
+    ];
 }
 
 impl FromWorld for HandleMap<SfxKey> {
     fn from_world(world: &mut World) -> Self {
-        let asset_server = world.resource::<AssetServer>();
-        [
-            (
-                SfxKey::ButtonHover,
-                asset_server.load("audio/sfx/button_hover.ogg"),
-            ),
-            (
-                SfxKey::ButtonPress,
-                asset_server.load("audio/sfx/button_press.ogg"),
-            ),
-            (SfxKey::Step1, asset_server.load("audio/sfx/step1.ogg")),
-            (SfxKey::Step2, asset_server.load("audio/sfx/step2.ogg")),
-            (SfxKey::Step3, asset_server.load("audio/sfx/step3.ogg")),
-            (SfxKey::Step4, asset_server.load("audio/sfx/step4.ogg")),
-        ]
-        .into()
+        let manifest = world.resource::<AssetManifest>();
+        HandleMap::load_paths(world.resource::<AssetServer>(), &manifest.sfx)
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect, Deserialize, Debug)]
 pub enum SoundtrackKey {
+    Title,
     Credits,
     Gameplay,
+    /// Stems layered on top of the gameplay track as the game gets tense.
+    GameplayPercussion,
+    GameplayBass,
+    GameplayLead,
 }
 
 impl AssetKey for SoundtrackKey {
     type Asset = AudioSource;
+    const ALL: &'static [Self] = &[
+        SoundtrackKey::Title,
+        SoundtrackKey::Credits,
+        SoundtrackKey::Gameplay,
+        SoundtrackKey::GameplayPercussion,
+        SoundtrackKey::GameplayBass,
+        SoundtrackKey::GameplayLead,
+    ];
 }
 
 impl FromWorld for HandleMap<SoundtrackKey> {
     fn from_world(world: &mut World) -> Self {
-        let asset_server = world.resource::<AssetServer>();
-        [
-            (
-                SoundtrackKey::Credits,
-                asset_server.load("audio/soundtracks/Monkeys Spinning Monkeys.ogg"),
-            ),
-            (
-                SoundtrackKey::Gameplay,
-                asset_server.load("audio/soundtracks/Fluffing A Duck.ogg"),
-            ),
-        ]
-        .into()
+        let manifest = world.resource::<AssetManifest>();
+        HandleMap::load_paths(world.resource::<AssetServer>(), &manifest.soundtracks)
     }
 }
 
-pub trait AssetKey: Sized {
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect, Serialize, Deserialize, Debug)]
+pub enum LevelKey {
+    Orbits,
+    Relay,
+    OuterRim,
+    Endless,
+    Sandbox,
+    Tutorial,
+    /// Generated at runtime from the date, so it has no entry in the manifest.
+    Daily,
+}
+
+impl AssetKey for LevelKey {
+    type Asset = Level;
+    // The daily challenge is left out, it has no entry in the manifest.
+    const ALL: &'static [Self] = &[
+        LevelKey::Orbits,
+        LevelKey::Relay,
+        LevelKey::OuterRim,
+        LevelKey::Endless,
+        LevelKey::Sandbox,
+        LevelKey::Tutorial,
+    ];
+}
+
+impl FromWorld for HandleMap<LevelKey> {
+    fn from_world(world: &mut World) -> Self {
+        let manifest = world.resource::<AssetManifest>();
+        HandleMap::load_paths(world.resource::<AssetServer>(), &manifest.levels)
+    }
+}
+
+pub trait AssetKey: Sized + 'static {
     type Asset: Asset;
+    /// Every key that needs an entry in the manifest.
+    const ALL: &'static [Self];
 }
 
 #[derive(Resource, Reflect, Deref, DerefMut)]
@@ -119,9 +203,50 @@ where
     }
 }
 
-impl<K: AssetKey> HandleMap<K> {
-    pub fn all_loaded(&self, asset_server: &AssetServer) -> bool {
-        self.values()
-            .all(|x| asset_server.is_loaded_with_dependencies(x))
+/// How far along a [`HandleMap`] is with loading.
+#[derive(Debug, Default)]
+pub struct LoadProgress {
+    pub loaded: usize,
+    pub total: usize,
+    /// One message per asset that failed to load.
+    pub errors: Vec<String>,
+}
+
+impl LoadProgress {
+    pub fn is_done(&self) -> bool {
+        self.loaded == self.total
+    }
+}
+
+impl<K: AssetKey + Copy + Eq + std::hash::Hash + std::fmt::Debug> HandleMap<K> {
+    fn load_paths(asset_server: &AssetServer, paths: &HashMap<K, String>) -> Self {
+        paths
+            .iter()
+            .map(|(key, path)| (*key, asset_server.load(path.clone())))
+            .collect::<HashMap<_, _>>()
+            .into()
+    }
+
+    /// A key without an entry in the manifest has nothing loaded for it, so it counts
+    /// as an asset that can never finish loading.
+    pub fn progress(&self, asset_server: &AssetServer) -> LoadProgress {
+        let missing: Vec<_> = K::ALL
+            .iter()
+            .filter(|key| !self.contains_key(*key))
+            .map(|key| format!("{key:?} has no entry in the asset manifest"))
+            .collect();
+        let mut progress = LoadProgress {
+            total: self.len() + missing.len(),
+            errors: missing,
+            ..default()
+        };
+        for handle in self.values() {
+            if asset_server.is_loaded_with_dependencies(handle) {
+                progress.loaded += 1;
+            } else if let Some(LoadState::Failed(error)) = asset_server.get_load_state(handle) {
+                progress.errors.push(error.to_string());
+            }
+        }
+        progress
     }
 }
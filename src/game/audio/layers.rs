@@ -0,0 +1,122 @@
+//! Fade the stems of the gameplay soundtrack in and out as the game gets tense.
+
+use bevy::prelude::*;
+
+use super::soundtrack::{move_towards, MusicLayerLevel};
+use crate::{
+    game::{
+        clock::{TimeScale, TIME_SCALES},
+        resource::GameResourceDemand,
+        spawn::connection::{ConnectionAnchor, ConnectionUnderConstruction},
+    },
+    screen::Screen,
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(MusicLayer, MusicTension)>();
+    app.init_resource::<MusicTension>();
+    app.add_systems(OnExit(Screen::Playing), reset_music_tension);
+    app.add_systems(
+        Update,
+        (
+            update_music_tension
+                .in_set(AppSet::Update)
+                .run_if(in_state(Screen::Playing)),
+            update_music_layers.after(AppSet::Update),
+        ),
+    );
+}
+
+/// A demand counts as overdue once this much of its deadline has passed.
+const OVERDUE_FRACTION: f32 = 0.5;
+
+/// The percussion is fully in once the network has this many connections.
+const FULL_NETWORK_SIZE: u32 = 8;
+
+/// The bass is fully in once this many orders are overdue.
+const FULL_OVERDUE_ORDERS: u32 = 3;
+
+/// One stem of a layered soundtrack.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum MusicLayer {
+    /// Grows with the size of the network.
+    Percussion,
+    /// Grows with the number of overdue orders.
+    Bass,
+    /// Comes in while the game is sped up.
+    Lead,
+}
+
+impl MusicLayer {
+    /// Seconds to fade fully in and fully out.
+    fn envelope(&self) -> (f32, f32) {
+        match self {
+            MusicLayer::Percussion => (4.0, 6.0),
+            // Overdue orders should be heard straight away.
+            MusicLayer::Bass => (1.0, 5.0),
+            MusicLayer::Lead => (2.0, 2.0),
+        }
+    }
+
+    /// How loud this layer should be, between 0 and 1.
+    fn target(&self, tension: &MusicTension) -> f32 {
+        match self {
+            MusicLayer::Percussion => tension.network as f32 / FULL_NETWORK_SIZE as f32,
+            MusicLayer::Bass => tension.overdue as f32 / FULL_OVERDUE_ORDERS as f32,
+            MusicLayer::Lead => {
+                let fastest = TIME_SCALES[TIME_SCALES.len() - 1];
+                (tension.speed - TIME_SCALES[0]) / (fastest - TIME_SCALES[0])
+            }
+        }
+        .clamp(0.0, 1.0)
+    }
+}
+
+/// What the layered soundtrack reacts to.
+#[derive(Resource, Reflect, Debug, Default, PartialEq)]
+#[reflect(Resource)]
+pub struct MusicTension {
+    pub overdue: u32,
+    pub network: u32,
+    pub speed: f32,
+}
+
+fn reset_music_tension(mut tension: ResMut<MusicTension>) {
+    *tension = MusicTension::default();
+}
+
+fn update_music_tension(
+    time_scale: Res<TimeScale>,
+    demand_query: Query<&GameResourceDemand>,
+    connection_query: Query<(), (With<ConnectionAnchor>, Without<ConnectionUnderConstruction>)>,
+    mut tension: ResMut<MusicTension>,
+) {
+    tension.set_if_neq(MusicTension {
+        overdue: demand_query
+            .iter()
+            .filter(|demand| demand.deadline.fraction() >= OVERDUE_FRACTION)
+            .count() as u32,
+        network: connection_query.iter().count() as u32,
+        speed: if time_scale.paused {
+            TIME_SCALES[0]
+        } else {
+            time_scale.speed
+        },
+    });
+}
+
+/// Layers keep fading while the game is paused, so this runs on real time.
+fn update_music_layers(
+    time: Res<Time<Real>>,
+    tension: Res<MusicTension>,
+    mut layer_query: Query<(&MusicLayer, &mut MusicLayerLevel)>,
+) {
+    for (layer, mut level) in &mut layer_query {
+        let target = layer.target(&tension);
+        let (attack, release) = layer.envelope();
+        let fade_secs = if target > level.0 { attack } else { release };
+        level.0 = move_towards(level.0, target, time.delta_seconds() / fade_secs);
+    }
+}
@@ -1,8 +1,59 @@
+pub mod layers;
 pub mod sfx;
 pub mod soundtrack;
 
 use bevy::prelude::*;
 
+use crate::settings::Settings;
+
 pub fn plugin(app: &mut App) {
-    app.add_plugins((sfx::plugin, soundtrack::plugin));
+    app.register_type::<AudioBus>();
+    app.add_plugins((layers::plugin, sfx::plugin, soundtrack::plugin));
+    app.add_systems(
+        Update,
+        apply_bus_volumes.run_if(resource_changed::<Settings>),
+    );
+}
+
+/// The mixer channel a sound plays through. Each bus has its own volume setting.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum AudioBus {
+    Music,
+    Ui,
+    Gameplay,
+}
+
+impl AudioBus {
+    /// The bus volume, not including the master volume.
+    pub fn volume(&self, settings: &Settings) -> f32 {
+        match self {
+            AudioBus::Music => settings.music_volume,
+            AudioBus::Ui => settings.ui_volume,
+            AudioBus::Gameplay => settings.sfx_volume,
+        }
+    }
+}
+
+/// Playing sinks ignore changes to [`GlobalVolume`], so update them directly.
+/// Music fades are handled by the soundtrack itself. Positional sounds play through
+/// a [`SpatialAudioSink`] instead.
+fn apply_bus_volumes(
+    settings: Res<Settings>,
+    sink_query: Query<(&AudioSink, &AudioBus)>,
+    spatial_sink_query: Query<(&SpatialAudioSink, &AudioBus)>,
+) {
+    let sinks = sink_query
+        .iter()
+        .map(|(sink, bus)| (sink as &dyn AudioSinkPlayback, bus))
+        .chain(
+            spatial_sink_query
+                .iter()
+                .map(|(sink, bus)| (sink as &dyn AudioSinkPlayback, bus)),
+        );
+    for (sink, bus) in sinks {
+        if *bus != AudioBus::Music {
+            sink.set_volume(settings.master_volume * bus.volume(&settings));
+        }
+    }
 }
@@ -1,7 +1,14 @@
-use bevy::{audio::PlaybackMode, prelude::*};
+use bevy::{
+    audio::{PlaybackMode, SpatialScale, Volume},
+    prelude::*,
+};
 use rand::seq::SliceRandom;
 
-use crate::game::assets::{HandleMap, SfxKey};
+use super::AudioBus;
+use crate::{
+    game::assets::{HandleMap, SfxKey},
+    settings::Settings,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.observe(play_sfx);
@@ -11,24 +18,67 @@ fn play_sfx(
     trigger: Trigger<PlaySfx>,
     mut commands: Commands,
     sfx_handles: Res<HandleMap<SfxKey>>,
+    settings: Res<Settings>,
 ) {
-    let sfx_key = match trigger.event() {
-        PlaySfx::Key(key) => *key,
-        PlaySfx::RandomStep => random_step(),
+    let (sfx_key, position) = match trigger.event() {
+        PlaySfx::Key(key) => (*key, None),
+        PlaySfx::At(key, position) => (*key, Some(*position)),
+        PlaySfx::RandomStep => (random_step(), None),
     };
-    commands.spawn(AudioSourceBundle {
-        source: sfx_handles[&sfx_key].clone_weak(),
-        settings: PlaybackSettings {
-            mode: PlaybackMode::Despawn,
-            ..default()
+    let bus = sfx_key.bus();
+    let mut entity = commands.spawn((
+        AudioSourceBundle {
+            source: sfx_handles[&sfx_key].clone_weak(),
+            settings: PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                volume: Volume::new(bus.volume(&settings)),
+                spatial: position.is_some(),
+                spatial_scale: Some(SpatialScale::new_2d(1.0 / SFX_FALLOFF_DISTANCE)),
+                ..default()
+            },
         },
-    });
+        bus,
+    ));
+    if let Some(position) = position {
+        entity.insert(TransformBundle::from_transform(
+            Transform::from_translation(position),
+        ));
+    }
+}
+
+/// Positional sounds play at full volume within this distance of the listener
+/// and fall off with the square of the distance beyond it.
+const SFX_FALLOFF_DISTANCE: f32 = 150.0;
+
+/// Distance between the listener's ears, in world units.
+/// Wider ears pan positional sounds more strongly between the speakers.
+pub const LISTENER_EAR_GAP: f32 = 300.0;
+
+impl SfxKey {
+    /// The bus this sound effect plays through.
+    pub fn bus(&self) -> AudioBus {
+        match self {
+            SfxKey::ButtonHover | SfxKey::ButtonPress => AudioBus::Ui,
+            SfxKey::Step1
+            | SfxKey::Step2
+            | SfxKey::Step3
+            | SfxKey::Step4
+            | SfxKey::Alert
+            | SfxKey::Delivery
+            | SfxKey::LinkBreak
+            | SfxKey::Departure
+            | SfxKey::Alignment => AudioBus::Gameplay,
+        }
+    }
 }
 
 /// Trigger this event to play a single sound effect.
 #[derive(Event)]
 pub enum PlaySfx {
     Key(SfxKey),
+    /// Play from a position in the world, panned and attenuated
+    /// relative to the camera's [`SpatialListener`].
+    At(SfxKey, Vec3),
     RandomStep,
 }
 
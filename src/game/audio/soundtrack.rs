@@ -1,40 +1,89 @@
-use bevy::{audio::PlaybackMode, prelude::*};
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+};
 
-use crate::game::assets::{HandleMap, SoundtrackKey};
+use super::{layers::MusicLayer, AudioBus};
+use crate::{
+    game::assets::{HandleMap, SoundtrackKey},
+    settings::Settings,
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.register_type::<IsSoundtrack>();
+    app.register_type::<(IsSoundtrack, MusicLayerLevel)>();
+    app.register_type::<MusicDuck>();
+    app.init_resource::<MusicDuck>();
     app.observe(play_soundtrack);
+    app.observe(duck_music);
+    app.add_systems(Update, (tick_music_duck, update_soundtrack_fades).chain());
 }
 
+/// How long it takes one soundtrack to fade into the next.
+const CROSSFADE_SECS: f32 = 1.5;
+
+/// How loud the music stays while it is ducked.
+const DUCKED_VOLUME: f32 = 0.35;
+
+/// How quickly the music dips and recovers around a duck.
+const DUCK_FADE_SECS: f32 = 0.25;
+
 fn play_soundtrack(
     trigger: Trigger<PlaySoundtrack>,
     mut commands: Commands,
     soundtrack_handles: Res<HandleMap<SoundtrackKey>>,
-    soundtrack_query: Query<Entity, With<IsSoundtrack>>,
+    mut soundtrack_query: Query<&mut IsSoundtrack>,
 ) {
-    for entity in &soundtrack_query {
-        commands.entity(entity).despawn_recursive();
+    // Fade out whatever is playing instead of cutting it off.
+    for mut soundtrack in &mut soundtrack_query {
+        soundtrack.target = 0.0;
     }
 
     let soundtrack_key = match trigger.event() {
         PlaySoundtrack::Key(key) => *key,
         PlaySoundtrack::Disable => return,
     };
-    commands.spawn((
-        AudioSourceBundle {
-            source: soundtrack_handles[&soundtrack_key].clone_weak(),
-            settings: PlaybackSettings {
-                mode: PlaybackMode::Loop,
-                ..default()
+    let bundle = |key: SoundtrackKey| {
+        (
+            AudioSourceBundle {
+                source: soundtrack_handles[&key].clone_weak(),
+                settings: PlaybackSettings {
+                    mode: PlaybackMode::Loop,
+                    volume: Volume::new(0.0),
+                    ..default()
+                },
             },
-        },
-        IsSoundtrack,
-    ));
+            AudioBus::Music,
+            IsSoundtrack {
+                level: 0.0,
+                target: 1.0,
+            },
+        )
+    };
+    commands.spawn(bundle(soundtrack_key));
+
+    // Stems start together with the main track so they stay in time,
+    // and stay silent until their layer is brought in.
+    for (layer, stem_key) in soundtrack_key.layers() {
+        commands.spawn((bundle(*stem_key), *layer, MusicLayerLevel::default()));
+    }
+}
+
+impl SoundtrackKey {
+    /// The stems layered on top of this soundtrack.
+    fn layers(&self) -> &'static [(MusicLayer, SoundtrackKey)] {
+        match self {
+            SoundtrackKey::Gameplay => &[
+                (MusicLayer::Percussion, SoundtrackKey::GameplayPercussion),
+                (MusicLayer::Bass, SoundtrackKey::GameplayBass),
+                (MusicLayer::Lead, SoundtrackKey::GameplayLead),
+            ],
+            _ => &[],
+        }
+    }
 }
 
 /// Trigger this event to play or disable the soundtrack.
-/// Playing a new soundtrack will overwrite the previous one.
+/// Playing a new soundtrack crossfades from the previous one.
 /// Soundtracks will loop.
 #[derive(Event)]
 pub enum PlaySoundtrack {
@@ -42,7 +91,96 @@ pub enum PlaySoundtrack {
     Disable,
 }
 
-/// Marker component for the soundtrack entity so we can find it later.
+/// Trigger this event to lower the music for a while, e.g. so an alert can be heard.
+#[derive(Event)]
+pub struct DuckMusic {
+    pub duration: f32,
+}
+
+/// Component for the soundtrack entity so we can find it later.
+/// Tracks how far the soundtrack has faded in.
 #[derive(Component, Reflect)]
 #[reflect(Component)]
-struct IsSoundtrack;
+struct IsSoundtrack {
+    level: f32,
+    /// The soundtrack is despawned once it has faded out to 0.
+    target: f32,
+}
+
+/// How far a layered stem has faded in, following its [`MusicLayer`] envelope.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub(super) struct MusicLayerLevel(pub f32);
+
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+struct MusicDuck {
+    /// Seconds left until the music recovers.
+    remaining: f32,
+    level: f32,
+}
+
+impl Default for MusicDuck {
+    fn default() -> Self {
+        Self {
+            remaining: 0.0,
+            level: 1.0,
+        }
+    }
+}
+
+fn duck_music(trigger: Trigger<DuckMusic>, mut duck: ResMut<MusicDuck>) {
+    duck.remaining = duck.remaining.max(trigger.event().duration);
+}
+
+/// Audio keeps playing while the game is paused, so fades run on real time.
+fn tick_music_duck(time: Res<Time<Real>>, mut duck: ResMut<MusicDuck>) {
+    duck.remaining = (duck.remaining - time.delta_seconds()).max(0.0);
+    let target = if duck.remaining > 0.0 {
+        DUCKED_VOLUME
+    } else {
+        1.0
+    };
+    duck.level = move_towards(duck.level, target, time.delta_seconds() / DUCK_FADE_SECS);
+}
+
+fn update_soundtrack_fades(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    duck: Res<MusicDuck>,
+    mut soundtrack_query: Query<(
+        Entity,
+        &mut IsSoundtrack,
+        Option<&MusicLayerLevel>,
+        Option<&AudioSink>,
+    )>,
+) {
+    let step = time.delta_seconds() / CROSSFADE_SECS;
+    for (entity, mut soundtrack, layer, sink) in &mut soundtrack_query {
+        soundtrack.level = move_towards(soundtrack.level, soundtrack.target, step);
+        if soundtrack.target == 0.0 && soundtrack.level == 0.0 {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        // The sink only appears once the audio has started playing.
+        if let Some(sink) = sink {
+            sink.set_volume(
+                settings.master_volume
+                    * AudioBus::Music.volume(&settings)
+                    * soundtrack.level
+                    * layer.map_or(1.0, |layer| layer.0)
+                    * duck.level,
+            );
+        }
+    }
+}
+
+pub(super) fn move_towards(current: f32, target: f32, step: f32) -> f32 {
+    if current < target {
+        (current + step).min(target)
+    } else {
+        (current - step).max(target)
+    }
+}
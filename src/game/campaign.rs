@@ -0,0 +1,120 @@
+//! The campaign: an ordered list of levels, unlocked one after another.
+//! Progress is saved whenever a level is completed.
+
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    assets::{HandleMap, LevelKey},
+    ledger::Ledger,
+    objective::{LevelCompleted, LevelFailed},
+    spawn::level::Level,
+};
+use crate::{persistence, screen::Screen};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<CurrentLevel>();
+    app.insert_resource(CurrentLevel(CAMPAIGN[0]));
+    app.insert_resource(CampaignProgress::load());
+
+    app.observe(record_completed_level);
+    app.observe(show_results);
+}
+
+const CAMPAIGN_KEY: &str = "campaign";
+
+/// Campaign levels in the order they unlock.
+pub const CAMPAIGN: [LevelKey; 3] = [LevelKey::Orbits, LevelKey::Relay, LevelKey::OuterRim];
+
+/// The level that is played when entering [`Screen::Playing`].
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Resource)]
+pub struct CurrentLevel(pub LevelKey);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelStatus {
+    Locked,
+    Unlocked,
+    Completed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[serde(default)]
+struct LevelRecord {
+    best_score: u32,
+    best_stars: u32,
+}
+
+/// Levels the player has completed and their best scores.
+#[derive(Resource, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct CampaignProgress {
+    completed: HashMap<LevelKey, LevelRecord>,
+}
+
+impl CampaignProgress {
+    fn load() -> Self {
+        persistence::load(CAMPAIGN_KEY).unwrap_or_default()
+    }
+
+    fn save(&self) {
+        persistence::save(CAMPAIGN_KEY, self);
+    }
+
+    /// The first level is always unlocked, every other one once the level before it is completed.
+    pub fn status(&self, level: LevelKey) -> LevelStatus {
+        if self.completed.contains_key(&level) {
+            return LevelStatus::Completed;
+        }
+
+        let index = CAMPAIGN.iter().position(|key| *key == level);
+        match index {
+            Some(0) => LevelStatus::Unlocked,
+            Some(index) if self.completed.contains_key(&CAMPAIGN[index - 1]) => {
+                LevelStatus::Unlocked
+            }
+            _ => LevelStatus::Locked,
+        }
+    }
+
+    pub fn best_score(&self, level: LevelKey) -> Option<u32> {
+        self.completed.get(&level).map(|record| record.best_score)
+    }
+
+    pub fn best_stars(&self, level: LevelKey) -> Option<u32> {
+        self.completed.get(&level).map(|record| record.best_stars)
+    }
+}
+
+/// The level after `level` in the campaign, if there is one.
+pub fn next_level(level: LevelKey) -> Option<LevelKey> {
+    let index = CAMPAIGN.iter().position(|key| *key == level)?;
+    CAMPAIGN.get(index + 1).copied()
+}
+
+fn record_completed_level(
+    _trigger: Trigger<LevelCompleted>,
+    current_level: Res<CurrentLevel>,
+    level_handles: Res<HandleMap<LevelKey>>,
+    levels: Res<Assets<Level>>,
+    ledger: Res<Ledger>,
+    mut progress: ResMut<CampaignProgress>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    next_screen.set(Screen::Victory);
+    if !CAMPAIGN.contains(&current_level.0) {
+        return;
+    }
+
+    let stars = levels
+        .get(&level_handles[&current_level.0])
+        .map_or(1, |level| level.stars(ledger.score));
+    let record = progress.completed.entry(current_level.0).or_default();
+    record.best_score = record.best_score.max(ledger.score);
+    record.best_stars = record.best_stars.max(stars);
+    progress.save();
+}
+
+fn show_results(_trigger: Trigger<LevelFailed>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Results);
+}
@@ -0,0 +1,133 @@
+//! Track elapsed game time in cycles and control how fast the simulation runs.
+
+use bevy::prelude::*;
+
+use super::{assets::LevelKey, campaign::CurrentLevel};
+use crate::{
+    screen::{Menu, Screen},
+    settings::{BindingAction, Settings},
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<GameClock>();
+    app.init_resource::<GameClock>();
+
+    app.register_type::<TimeScale>();
+    app.init_resource::<TimeScale>();
+
+    app.add_systems(OnEnter(Screen::Playing), reset_clock);
+    app.add_systems(OnExit(Screen::Playing), reset_time_scale);
+    app.add_systems(
+        Update,
+        (
+            tick_game_clock.in_set(AppSet::TickTimers),
+            record_time_scale_input
+                .in_set(AppSet::RecordInput)
+                .run_if(in_state(Menu::None)),
+            apply_time_scale.run_if(resource_changed::<TimeScale>.or_else(state_changed::<Menu>)),
+        )
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// How many seconds of game time make up a single cycle.
+pub const CYCLE_DURATION_SECS: f32 = 30.0;
+
+/// The speeds the player can pick between, in order.
+pub const TIME_SCALES: [f32; 3] = [1.0, 2.0, 4.0];
+
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct GameClock {
+    /// Game time in seconds since the level started.
+    pub elapsed: f32,
+}
+
+impl GameClock {
+    /// The number of whole cycles that have passed.
+    pub fn cycle(&self) -> u32 {
+        (self.elapsed / CYCLE_DURATION_SECS) as u32
+    }
+
+    /// Progress through the current cycle, between 0 and 1.
+    pub fn cycle_progress(&self) -> f32 {
+        (self.elapsed % CYCLE_DURATION_SECS) / CYCLE_DURATION_SECS
+    }
+}
+
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource)]
+pub struct TimeScale {
+    pub speed: f32,
+    pub paused: bool,
+}
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        Self {
+            speed: TIME_SCALES[0],
+            paused: false,
+        }
+    }
+}
+
+fn reset_clock(
+    settings: Res<Settings>,
+    current_level: Res<CurrentLevel>,
+    mut clock: ResMut<GameClock>,
+    mut time_scale: ResMut<TimeScale>,
+) {
+    *clock = GameClock::default();
+    *time_scale = TimeScale {
+        speed: settings.time_scale,
+        // The sandbox starts out still so the system can be edited first.
+        paused: current_level.0 == LevelKey::Sandbox,
+    };
+}
+
+fn reset_time_scale(mut time: ResMut<Time<Virtual>>) {
+    time.set_relative_speed(1.0);
+    time.unpause();
+}
+
+fn tick_game_clock(time: Res<Time>, mut clock: ResMut<GameClock>) {
+    clock.elapsed += time.delta_seconds();
+}
+
+fn record_time_scale_input(
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    mut time_scale: ResMut<TimeScale>,
+) {
+    let bindings = &settings.key_bindings;
+    if input.just_pressed(bindings.get(BindingAction::TogglePause)) {
+        time_scale.paused = !time_scale.paused;
+    }
+
+    let actions = [
+        BindingAction::SpeedNormal,
+        BindingAction::SpeedFast,
+        BindingAction::SpeedFastest,
+    ];
+    for (action, speed) in actions.into_iter().zip(TIME_SCALES) {
+        if input.just_pressed(bindings.get(action)) {
+            time_scale.speed = speed;
+            time_scale.paused = false;
+        }
+    }
+}
+
+/// The simulation also stops while a menu is open on top of the playing screen.
+fn apply_time_scale(
+    time_scale: Res<TimeScale>,
+    menu: Res<State<Menu>>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    time.set_relative_speed(time_scale.speed);
+    if time_scale.paused || *menu.get() != Menu::None {
+        time.pause();
+    } else {
+        time.unpause();
+    }
+}
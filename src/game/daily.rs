@@ -0,0 +1,305 @@
+//! The daily challenge: a star system generated from the date, with fixed objectives,
+//! so everyone playing on the same day gets the same level and seed.
+//! Each day allows a single attempt, and a summary can be copied to the clipboard once it ends.
+
+use std::fmt::Write;
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    assets::{HandleMap, LevelKey},
+    campaign::CurrentLevel,
+    clock::GameClock,
+    ledger::Ledger,
+    objective::{LevelCompleted, LevelFailed, Objective},
+    resource::{GameResource, OverflowPolicy},
+    rng::RunSeed,
+    spawn::{
+        level::Level,
+        planet::{SatelliteLayout, SatelliteRole},
+    },
+    statistics::RunStatistics,
+};
+use crate::{calendar::Date, clipboard::CopyStatus, persistence, screen::Screen};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(DailyAttempts::load());
+    app.init_resource::<DailyResult>();
+    app.add_systems(OnEnter(Screen::Playing), reset_daily_result);
+
+    app.observe(start_daily);
+    app.observe(share_completed_daily);
+    app.observe(share_failed_daily);
+}
+
+const DAILY_KEY: &str = "daily";
+
+/// How many times each day's challenge can be played.
+pub const DAILY_ATTEMPTS: u32 = 1;
+
+/// The most squares shown for deliveries or failures in a single cycle of the summary.
+const MAX_SQUARES: u32 = 10;
+
+/// How many attempts have been used at the most recently played challenge.
+#[derive(Resource, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct DailyAttempts {
+    /// The day of the challenge, as `YYYY-MM-DD`.
+    date: String,
+    attempts: u32,
+}
+
+impl DailyAttempts {
+    fn load() -> Self {
+        persistence::load(DAILY_KEY).unwrap_or_default()
+    }
+
+    fn save(&self) {
+        persistence::save(DAILY_KEY, self);
+    }
+
+    pub fn remaining(&self, date: Date) -> u32 {
+        if self.date == date.to_string() {
+            DAILY_ATTEMPTS.saturating_sub(self.attempts)
+        } else {
+            DAILY_ATTEMPTS
+        }
+    }
+
+    fn use_attempt(&mut self, date: Date) {
+        let date = date.to_string();
+        if self.date != date {
+            self.date = date;
+            self.attempts = 0;
+        }
+        self.attempts += 1;
+    }
+}
+
+/// The shareable summary of the daily challenge that ended most recently.
+#[derive(Resource, Debug, Default)]
+pub struct DailyResult {
+    /// The day of the challenge being played, which the summary is stamped with
+    /// even when the run ends after midnight.
+    pub date: Option<Date>,
+    pub summary: Option<String>,
+    /// How copying the summary to the clipboard went, once the player asked for it.
+    pub copy: Option<CopyStatus>,
+}
+
+/// Start today's challenge, if there is an attempt left.
+#[derive(Event, Debug)]
+pub struct StartDaily;
+
+/// The seed for the challenge on `date`. Every player gets the same one.
+pub fn daily_seed(date: Date) -> u64 {
+    let days = date.year as u64 * 10_000 + date.month as u64 * 100 + date.day as u64;
+    // SplitMix64, so neighbouring days get unrelated seeds.
+    let mut z = days.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Lay out a star system from `seed`, with the same objectives every day.
+pub fn generate_level(date: Date, seed: u64) -> Level {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    // Every system has two spawners and two consumers, the rest are relays.
+    let count = rng.gen_range(5..=7);
+    let mut roles = vec![
+        SatelliteRole::Spawner(GameResource::Material),
+        SatelliteRole::Spawner(GameResource::Material),
+        SatelliteRole::Consumer(vec![GameResource::Material; 3]),
+        SatelliteRole::Consumer(vec![GameResource::Material; 2]),
+    ];
+    roles.resize(count, SatelliteRole::Relay);
+
+    let mut orbit_radius = 20.0;
+    let mut satellites = Vec::with_capacity(count);
+    while !roles.is_empty() {
+        let role = roles.swap_remove(rng.gen_range(0..roles.len()));
+        orbit_radius += rng.gen_range(36.0..44.0);
+        satellites.push(SatelliteLayout::random(&mut rng, orbit_radius, role));
+    }
+
+    Level {
+        name: format!("Daily {date}"),
+        objectives: vec![Objective::Survive(5), Objective::FailuresBelow(6)],
+        star_scores: [1500, 2500],
+        satellites,
+        director: None,
+        tutorial: Vec::new(),
+        events: None,
+        alignments: Vec::new(),
+        overflow: OverflowPolicy::default(),
+    }
+}
+
+fn start_daily(
+    _trigger: Trigger<StartDaily>,
+    mut attempts: ResMut<DailyAttempts>,
+    mut level_handles: ResMut<HandleMap<LevelKey>>,
+    mut levels: ResMut<Assets<Level>>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut run_seed: ResMut<RunSeed>,
+    mut result: ResMut<DailyResult>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    let today = Date::today();
+    if attempts.remaining(today) == 0 {
+        return;
+    }
+    attempts.use_attempt(today);
+    attempts.save();
+
+    let seed = daily_seed(today);
+    level_handles.insert(LevelKey::Daily, levels.add(generate_level(today, seed)));
+    current_level.0 = LevelKey::Daily;
+    run_seed.0 = Some(seed);
+    result.date = Some(today);
+    next_screen.set(Screen::Playing);
+}
+
+/// The date is set right before the run starts, so it is kept.
+fn reset_daily_result(mut result: ResMut<DailyResult>) {
+    *result = DailyResult {
+        date: result.date,
+        ..default()
+    };
+}
+
+fn share_completed_daily(
+    _trigger: Trigger<LevelCompleted>,
+    current_level: Res<CurrentLevel>,
+    ledger: Res<Ledger>,
+    clock: Res<GameClock>,
+    statistics: Res<RunStatistics>,
+    result: ResMut<DailyResult>,
+) {
+    if current_level.0 == LevelKey::Daily {
+        share(true, &ledger, &clock, &statistics, result);
+    }
+}
+
+fn share_failed_daily(
+    _trigger: Trigger<LevelFailed>,
+    current_level: Res<CurrentLevel>,
+    ledger: Res<Ledger>,
+    clock: Res<GameClock>,
+    statistics: Res<RunStatistics>,
+    result: ResMut<DailyResult>,
+) {
+    if current_level.0 == LevelKey::Daily {
+        share(false, &ledger, &clock, &statistics, result);
+    }
+}
+
+fn share(
+    completed: bool,
+    ledger: &Ledger,
+    clock: &GameClock,
+    statistics: &RunStatistics,
+    mut result: ResMut<DailyResult>,
+) {
+    let date = result.date.unwrap_or_else(Date::today);
+    result.summary = Some(summarize(date, completed, ledger, clock, statistics));
+}
+
+/// A short text summary with one row of squares per cycle:
+/// green for each delivery, red for each failed order.
+fn summarize(
+    date: Date,
+    completed: bool,
+    ledger: &Ledger,
+    clock: &GameClock,
+    statistics: &RunStatistics,
+) -> String {
+    let outcome = if completed { "cleared" } else { "failed" };
+    let mut summary = format!(
+        "Cycles Daily {date} - {outcome}\nScore {}  {} delivered  {} failed\n",
+        ledger.score, ledger.fulfilled, ledger.failed,
+    );
+
+    let cycles = statistics.cycles.len().max(clock.cycle() as usize);
+    for cycle in 0..cycles {
+        let tally = statistics.cycles.get(cycle).copied().unwrap_or_default();
+        let deliveries = tally.deliveries.min(MAX_SQUARES) as usize;
+        let failures = tally.failures.min(MAX_SQUARES) as usize;
+        let row = if deliveries + failures == 0 {
+            "\u{2b1b}".to_string()
+        } else {
+            "\u{1f7e9}".repeat(deliveries) + &"\u{1f7e5}".repeat(failures)
+        };
+        let _ = writeln!(summary, "{} {row}", cycle + 1);
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEAP_DAY: Date = Date {
+        year: 2024,
+        month: 2,
+        day: 29,
+    };
+
+    #[test]
+    fn seed_is_fixed_for_a_date() {
+        assert_eq!(daily_seed(LEAP_DAY), 0x293c_c02a_ea25_e48a);
+        let next_day = Date {
+            year: 2024,
+            month: 3,
+            day: 1,
+        };
+        assert_ne!(daily_seed(LEAP_DAY), daily_seed(next_day));
+    }
+
+    #[test]
+    fn level_is_fixed_for_a_date() {
+        let level = generate_level(LEAP_DAY, daily_seed(LEAP_DAY));
+        assert_eq!(level.name, "Daily 2024-02-29");
+
+        // Role, orbit radius, size and starting angle of every satellite, from the sun outwards.
+        let expected = [
+            (
+                SatelliteRole::Spawner(GameResource::Material),
+                56.519,
+                9.045,
+                3.073,
+            ),
+            (
+                SatelliteRole::Consumer(vec![GameResource::Material; 2]),
+                97.684,
+                14.320,
+                2.900,
+            ),
+            (
+                SatelliteRole::Consumer(vec![GameResource::Material; 3]),
+                134.899,
+                5.709,
+                5.036,
+            ),
+            (SatelliteRole::Relay, 177.704, 7.483, 1.004),
+            (
+                SatelliteRole::Spawner(GameResource::Material),
+                217.161,
+                12.092,
+                2.938,
+            ),
+        ];
+        assert_eq!(level.satellites.len(), expected.len());
+        for (satellite, (role, orbit_radius, radius, orbit_position)) in
+            level.satellites.iter().zip(expected)
+        {
+            assert_eq!(satellite.role, role);
+            assert!((satellite.orbit_radius - orbit_radius).abs() < 1e-3);
+            assert!((satellite.radius - radius).abs() < 1e-3);
+            assert!((satellite.orbit_position - orbit_position).abs() < 1e-3);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! The difficulty director for endless mode. As a run goes on it places orders more often,
+//! adds consumer satellites and brings in new kinds of cargo, and it eases off after failed orders.
+//! Its curves are set per level, see `assets/levels/endless.level.ron`.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    assets::{HandleMap, LevelKey},
+    campaign::CurrentLevel,
+    ledger::OrderFailed,
+    resource::{DemandRules, GameResource, ResourceDemandTimer},
+    rng::GameRng,
+    spawn::{
+        level::Level,
+        planet::{Planet, SatelliteLayout, SatelliteRole, SpawnPlanet},
+    },
+};
+use crate::{screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Director>();
+    app.init_resource::<Director>();
+
+    app.add_systems(OnEnter(Screen::Playing), reset_director);
+    app.observe(ease_off_after_failure);
+    app.add_systems(
+        Update,
+        update_director
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// How new satellites are placed around the sun.
+const MIN_ORBIT_RADIUS: f32 = 60.0;
+const MAX_ORBIT_RADIUS: f32 = 340.0;
+
+/// How the director ramps up a level, from its `director` entry.
+/// Every curve is indexed by difficulty, in seconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectorConfig {
+    /// Seconds between waves of orders.
+    pub demand_interval: Curve,
+    /// The most orders a single consumer can place in one wave.
+    pub demands_per_wave: Curve,
+    /// Seconds between new consumer satellites.
+    pub consumer_interval: Curve,
+    /// No more satellites are added once there are this many.
+    pub max_satellites: usize,
+    /// Kinds of cargo that join in once the difficulty reaches the paired value.
+    /// A spawner for each one is added as it does.
+    #[serde(default)]
+    pub unlocks: Vec<(f32, GameResource)>,
+    /// Seconds of difficulty taken off for every failed order.
+    pub failure_setback: f32,
+}
+
+/// A piecewise linear curve through `(difficulty, value)` points, in order of difficulty.
+/// It holds its first and last values beyond either end.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Curve(pub Vec<(f32, f32)>);
+
+impl Curve {
+    pub fn sample(&self, difficulty: f32) -> f32 {
+        let points = &self.0;
+        let Some(&(first_x, first_y)) = points.first() else {
+            return 0.0;
+        };
+        if difficulty <= first_x {
+            return first_y;
+        }
+
+        for pair in points.windows(2) {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            if difficulty <= x1 {
+                let t = (difficulty - x0) / (x1 - x0).max(f32::EPSILON);
+                return y0 + (y1 - y0) * t;
+            }
+        }
+        points[points.len() - 1].1
+    }
+}
+
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct Director {
+    /// How far along the curves the run is. Advances with game time and is set back by failures.
+    pub difficulty: f32,
+    /// Seconds since a consumer satellite was last added.
+    since_consumer: f32,
+    /// How many entries of [`DirectorConfig::unlocks`] have been brought in.
+    unlocked: usize,
+}
+
+fn reset_director(mut director: ResMut<Director>) {
+    *director = Director::default();
+}
+
+fn ease_off_after_failure(
+    _trigger: Trigger<OrderFailed>,
+    current_level: Res<CurrentLevel>,
+    level_handles: Res<HandleMap<LevelKey>>,
+    levels: Res<Assets<Level>>,
+    mut director: ResMut<Director>,
+) {
+    let Some(config) = levels
+        .get(&level_handles[&current_level.0])
+        .and_then(|level| level.director.as_ref())
+    else {
+        return;
+    };
+    director.difficulty = (director.difficulty - config.failure_setback).max(0.0);
+}
+
+fn update_director(
+    mut commands: Commands,
+    time: Res<Time>,
+    current_level: Res<CurrentLevel>,
+    level_handles: Res<HandleMap<LevelKey>>,
+    levels: Res<Assets<Level>>,
+    mut director: ResMut<Director>,
+    mut rules: ResMut<DemandRules>,
+    mut demand_time: ResMut<ResourceDemandTimer>,
+    mut game_rng: ResMut<GameRng>,
+    planet_query: Query<(), With<Planet>>,
+) {
+    let Some(config) = levels
+        .get(&level_handles[&current_level.0])
+        .and_then(|level| level.director.as_ref())
+    else {
+        return;
+    };
+
+    director.difficulty += time.delta_seconds();
+    director.since_consumer += time.delta_seconds();
+    let difficulty = director.difficulty;
+
+    let interval = Duration::from_secs_f32(config.demand_interval.sample(difficulty).max(0.5));
+    if demand_time.timer.duration() != interval {
+        demand_time.timer.set_duration(interval);
+    }
+    rules.max_per_wave = config.demands_per_wave.sample(difficulty).round() as u32;
+
+    let mut satellites = planet_query.iter().count();
+
+    while let Some(&(at, resource)) = config.unlocks.get(director.unlocked) {
+        if difficulty < at {
+            break;
+        }
+        director.unlocked += 1;
+        if !rules.resources.contains(&resource) {
+            rules.resources.push(resource);
+        }
+        spawn_satellite(
+            &mut commands,
+            &mut game_rng,
+            satellites,
+            SatelliteRole::Spawner(resource),
+        );
+        satellites += 1;
+    }
+
+    if director.since_consumer >= config.consumer_interval.sample(difficulty) {
+        director.since_consumer = 0.0;
+        if satellites < config.max_satellites {
+            spawn_satellite(
+                &mut commands,
+                &mut game_rng,
+                satellites,
+                SatelliteRole::Consumer(Vec::new()),
+            );
+        }
+    }
+}
+
+/// Add a satellite on a random orbit. `index` is used to name it after the ones already there.
+fn spawn_satellite(
+    commands: &mut Commands,
+    game_rng: &mut GameRng,
+    index: usize,
+    role: SatelliteRole,
+) {
+    let orbit_radius = game_rng.rng.gen_range(MIN_ORBIT_RADIUS..MAX_ORBIT_RADIUS);
+    commands.trigger(SpawnPlanet {
+        index,
+        layout: SatelliteLayout::random(&mut game_rng.rng, orbit_radius, role),
+    });
+}
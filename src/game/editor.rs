@@ -0,0 +1,397 @@
+//! The sandbox level's editor. Satellites can be placed, dragged to new orbits and deleted
+//! with the mouse, and the whole system can be exported in the level file format.
+//! The inspector for the selected satellite lives in `screen::editor`.
+
+use bevy::{prelude::*, ui::RelativeCursorPosition};
+use bevy_vector_shapes::{painter::ShapePainter, shapes::DiscPainter};
+
+use super::{
+    assets::LevelKey,
+    campaign::CurrentLevel,
+    interaction::MousePosition,
+    palette::GamePalette,
+    resource::{
+        GameResourceDemand, GameResourceInStorage, GameResourceInTransit, OverflowPolicy, Refinery,
+        ResourceConsumer, ResourceContainer, ResourceSpawner, WaitingForRoom, Warehouse,
+    },
+    spawn::{
+        connection::{ConnectionAnchor, ConnectionTarget},
+        level::Level,
+        planet::{
+            OrbitalMovement, OrbitalPosition, Planet, SatelliteLayout, SatelliteProperties,
+            SatelliteRole, SpawnPlanet,
+        },
+    },
+};
+use crate::{
+    clipboard::{self, CopyStatus},
+    screen::{Menu, Screen},
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Editor>();
+    app.init_resource::<Editor>();
+    app.init_resource::<LastExport>();
+    app.init_resource::<PendingExport>();
+
+    app.add_systems(OnEnter(Screen::Playing), reset_editor);
+    app.observe(delete_satellite);
+    app.observe(export_level);
+    app.add_systems(
+        Update,
+        edit_with_mouse.in_set(AppSet::RecordInput).run_if(
+            in_state(Screen::Playing)
+                .and_then(in_state(Menu::None))
+                .and_then(editing),
+        ),
+    );
+    app.add_systems(
+        Update,
+        finish_export.run_if(in_state(Screen::Playing).and_then(in_sandbox)),
+    );
+    app.add_systems(
+        Update,
+        draw_selection
+            .in_set(AppSet::Render)
+            .run_if(in_state(Screen::Playing).and_then(in_sandbox)),
+    );
+}
+
+/// The range orbits can be dragged or set to.
+pub const MIN_ORBIT_RADIUS: f32 = 40.0;
+pub const MAX_ORBIT_RADIUS: f32 = 340.0;
+
+/// How far outside a satellite a click still picks it.
+const PICK_MARGIN: f32 = 10.0;
+
+/// Where exported levels are written on native builds.
+#[cfg(not(target_family = "wasm"))]
+const EXPORT_PATH: &str = "exports/sandbox.level.ron";
+
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct Editor {
+    pub tool: EditorTool,
+    pub selected: Option<Entity>,
+    /// Whether the selected satellite follows the mouse.
+    pub dragging: bool,
+}
+
+/// What the left mouse button does in the sandbox.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EditorTool {
+    /// Build connections, like in any other level.
+    #[default]
+    Connect,
+    /// Select satellites and drag them to new orbits.
+    Move,
+    /// Place new satellites.
+    Place,
+}
+
+/// A message describing where the most recent export went.
+#[derive(Resource, Debug, Default)]
+pub struct LastExport(pub Option<String>);
+
+/// An export waiting to hear back from the clipboard, and where else it went.
+#[derive(Resource, Debug, Default)]
+struct PendingExport(Option<(Option<String>, CopyStatus)>);
+
+/// Remove a satellite along with its connections, cargo and orders.
+#[derive(Event, Debug)]
+pub struct DeleteSatellite(pub Entity);
+
+/// Write the current system out in the level file format.
+#[derive(Event, Debug)]
+pub struct ExportLevel;
+
+pub fn in_sandbox(current_level: Res<CurrentLevel>) -> bool {
+    current_level.0 == LevelKey::Sandbox
+}
+
+/// The role a satellite currently has, going by its components.
+pub fn role_of(
+    spawner: Option<&ResourceSpawner>,
+    consumer: Option<&ResourceConsumer>,
+    warehouse: bool,
+) -> SatelliteRole {
+    match (spawner, consumer) {
+        (Some(spawner), _) => SatelliteRole::Spawner(spawner.spawn_type),
+        (None, Some(_)) => SatelliteRole::Consumer(Vec::new()),
+        (None, None) if warehouse => SatelliteRole::Warehouse,
+        (None, None) => SatelliteRole::Relay,
+    }
+}
+
+/// Whether the mouse edits the system instead of building connections.
+pub fn editing(editor: Res<Editor>) -> bool {
+    editor.tool != EditorTool::Connect
+}
+
+fn reset_editor(
+    mut editor: ResMut<Editor>,
+    mut last_export: ResMut<LastExport>,
+    mut pending_export: ResMut<PendingExport>,
+) {
+    *editor = Editor::default();
+    last_export.0 = None;
+    pending_export.0 = None;
+}
+
+/// The orbit radius and angle that put a satellite at `position`.
+fn orbit_at(position: Vec2) -> (f32, f32) {
+    let radius = position.length().clamp(MIN_ORBIT_RADIUS, MAX_ORBIT_RADIUS);
+    (radius, position.x.atan2(position.y))
+}
+
+fn edit_with_mouse(
+    mut commands: Commands,
+    mouse_position: Res<MousePosition>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    interaction_query: Query<&Interaction>,
+    cursor_query: Query<&RelativeCursorPosition>,
+    mut editor: ResMut<Editor>,
+    mut satellite_query: Query<(Entity, &mut OrbitalPosition, &SatelliteProperties)>,
+) {
+    let cursor = mouse_position.get_pos_3d().truncate();
+
+    if editor.dragging {
+        if mouse_button.pressed(MouseButton::Left) {
+            if let Some(Ok((_, mut orbit, _))) = editor.selected.map(|e| satellite_query.get_mut(e))
+            {
+                (orbit.radius, orbit.position) = orbit_at(cursor);
+            }
+            return;
+        }
+        editor.dragging = false;
+    }
+
+    // Clicks on the inspector or other interface elements aren't meant for the board.
+    if interaction_query
+        .iter()
+        .any(|interaction| *interaction != Interaction::None)
+        || cursor_query.iter().any(RelativeCursorPosition::mouse_over)
+    {
+        return;
+    }
+
+    let hit = satellite_query
+        .iter()
+        .find(|(_, orbit, properties)| {
+            orbit.get_euclidean_position().truncate().distance(cursor)
+                < properties.radius + PICK_MARGIN
+        })
+        .map(|(entity, ..)| entity);
+
+    if mouse_button.just_pressed(MouseButton::Right) {
+        if let Some(entity) = hit {
+            commands.trigger(DeleteSatellite(entity));
+        }
+        return;
+    }
+    if !mouse_button.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    match (editor.tool, hit) {
+        (_, Some(entity)) => {
+            editor.selected = Some(entity);
+            editor.dragging = editor.tool == EditorTool::Move;
+        }
+        (EditorTool::Place, None) => {
+            let (orbit_radius, orbit_position) = orbit_at(cursor);
+            commands.trigger(SpawnPlanet {
+                index: satellite_query.iter().count(),
+                layout: SatelliteLayout {
+                    radius: 8.0,
+                    orbit_radius,
+                    orbit_position,
+                    orbit_speed: 12.0 / orbit_radius,
+                    storage_size: 6,
+                    role: SatelliteRole::Relay,
+                    refinery: None,
+                },
+            });
+        }
+        (_, None) => editor.selected = None,
+    }
+}
+
+fn delete_satellite(
+    trigger: Trigger<DeleteSatellite>,
+    mut commands: Commands,
+    mut editor: ResMut<Editor>,
+    connection_query: Query<(Entity, &ConnectionAnchor, &ConnectionTarget)>,
+    storage_query: Query<(Entity, &GameResourceInStorage)>,
+    transit_query: Query<(Entity, &GameResourceInTransit)>,
+    waiting_query: Query<(Entity, &WaitingForRoom)>,
+    mut demand_query: Query<(Entity, &mut GameResourceDemand)>,
+) {
+    let satellite = trigger.event().0;
+
+    for (entity, anchor, target) in &connection_query {
+        if anchor.satellite == satellite
+            || matches!(target, ConnectionTarget::Satellite(target) if *target == satellite)
+        {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    for (entity, storage) in &storage_query {
+        if storage.satellite == satellite {
+            commands.entity(entity).despawn();
+        }
+    }
+    for (entity, waiting) in &waiting_query {
+        if waiting.satellite == satellite {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    // Cargo can't finish a route through a satellite that is gone,
+    // so its order goes back to waiting for other cargo.
+    let mut lost_claims = Vec::new();
+    for (entity, transit) in &transit_query {
+        if transit.route.contains(&satellite) {
+            lost_claims.extend(transit.claim);
+            commands.entity(entity).despawn();
+        }
+    }
+    for (entity, mut demand) in &mut demand_query {
+        if demand.satellite == satellite {
+            commands.entity(entity).despawn();
+        } else if lost_claims.contains(&entity) {
+            demand.claim = None;
+        }
+    }
+
+    commands.entity(satellite).despawn_recursive();
+    if editor.selected == Some(satellite) {
+        editor.selected = None;
+        editor.dragging = false;
+    }
+}
+
+fn export_level(
+    _trigger: Trigger<ExportLevel>,
+    satellite_query: Query<
+        (
+            &OrbitalPosition,
+            &OrbitalMovement,
+            &SatelliteProperties,
+            &ResourceContainer,
+            Option<&ResourceSpawner>,
+            Option<&ResourceConsumer>,
+            Option<&Refinery>,
+            Has<Warehouse>,
+        ),
+        With<Planet>,
+    >,
+    policy: Res<OverflowPolicy>,
+    mut last_export: ResMut<LastExport>,
+    mut pending_export: ResMut<PendingExport>,
+) {
+    let mut satellites: Vec<_> = satellite_query
+        .iter()
+        .map(
+            |(orbit, movement, properties, container, spawner, consumer, refinery, warehouse)| {
+                SatelliteLayout {
+                    radius: properties.radius,
+                    orbit_radius: orbit.radius,
+                    orbit_position: orbit.position.rem_euclid(std::f32::consts::TAU),
+                    orbit_speed: movement.speed,
+                    storage_size: container.storage_size,
+                    role: role_of(spawner, consumer, warehouse),
+                    refinery: refinery.map(|refinery| refinery.0),
+                }
+            },
+        )
+        .collect();
+    satellites.sort_by(|a, b| a.orbit_radius.total_cmp(&b.orbit_radius));
+
+    let level = Level {
+        name: "Sandbox".to_string(),
+        objectives: Vec::new(),
+        star_scores: [0, 0],
+        satellites,
+        director: None,
+        tutorial: Vec::new(),
+        events: None,
+        alignments: Vec::new(),
+        overflow: *policy,
+    };
+    let text = match ron::ser::to_string_pretty(&level, ron::ser::PrettyConfig::default()) {
+        Ok(text) => text,
+        Err(error) => {
+            warn!("Could not export the sandbox level: {error}");
+            last_export.0 = Some("Export failed".to_string());
+            return;
+        }
+    };
+
+    let path = write_export(&text);
+    last_export.0 = Some("Copying...".to_string());
+    pending_export.0 = Some((path, clipboard::copy(&text)));
+}
+
+/// Report where the export went once the clipboard has answered.
+fn finish_export(mut pending_export: ResMut<PendingExport>, mut last_export: ResMut<LastExport>) {
+    let Some(copied) = pending_export
+        .0
+        .as_ref()
+        .and_then(|(_, copy)| copy.result())
+    else {
+        return;
+    };
+    let Some((path, _)) = pending_export.0.take() else {
+        return;
+    };
+    last_export.0 = Some(match (path, copied) {
+        (Some(path), true) => format!("Saved to {path} and copied"),
+        (Some(path), false) => format!("Saved to {path}"),
+        (None, true) => "Copied to the clipboard".to_string(),
+        (None, false) => "Export failed".to_string(),
+    });
+}
+
+/// Write an exported level to disk, returning where it went.
+#[cfg(not(target_family = "wasm"))]
+fn write_export(text: &str) -> Option<String> {
+    let path = std::path::Path::new(EXPORT_PATH);
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(path, text));
+    match result {
+        Ok(()) => Some(EXPORT_PATH.to_string()),
+        Err(error) => {
+            warn!("Could not write the exported level: {error}");
+            None
+        }
+    }
+}
+
+/// There is no file system on the web, so exports only go to the clipboard.
+#[cfg(target_family = "wasm")]
+fn write_export(_text: &str) -> Option<String> {
+    None
+}
+
+fn draw_selection(
+    mut painter: ShapePainter,
+    editor: Res<Editor>,
+    palette: Res<GamePalette>,
+    satellite_query: Query<(&OrbitalPosition, &SatelliteProperties)>,
+) {
+    let Some(Ok((orbit, properties))) = editor.selected.map(|e| satellite_query.get(e)) else {
+        return;
+    };
+
+    painter.set_translation(orbit.get_euclidean_position());
+    painter.hollow = true;
+    painter.thickness = 1.5;
+    painter.set_color(palette.range_guide);
+    painter.circle(properties.radius + PICK_MARGIN);
+    painter.set_translation(Vec3::ZERO);
+}
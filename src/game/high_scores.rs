@@ -0,0 +1,158 @@
+//! Keep the best score for every level and seed, and a history of recent runs.
+//! Both are saved whenever a run ends.
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    assets::LevelKey,
+    campaign::CurrentLevel,
+    clock::GameClock,
+    ledger::Ledger,
+    objective::{LevelCompleted, LevelFailed},
+    rng::GameRng,
+};
+use crate::{calendar::Date, persistence};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(HighScores::load());
+    app.init_resource::<LatestRun>();
+
+    app.observe(record_completed_run);
+    app.observe(record_failed_run);
+}
+
+const HIGH_SCORES_KEY: &str = "high_scores";
+
+/// How many finished runs are kept in the history.
+const HISTORY_LENGTH: usize = 20;
+
+/// The outcome of a single run.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RunRecord {
+    pub level: LevelKey,
+    pub seed: u64,
+    /// The day the run was played, as `YYYY-MM-DD`.
+    pub date: String,
+    /// Game time in seconds.
+    pub duration: f32,
+    pub score: u32,
+    pub deliveries: u32,
+    pub failures: u32,
+    pub money: i32,
+    pub completed: bool,
+}
+
+impl RunRecord {
+    /// The run's game time as `minutes:seconds`.
+    pub fn duration_label(&self) -> String {
+        let minutes = (self.duration / 60.0) as u32;
+        let seconds = self.duration as u32 % 60;
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct HighScores {
+    /// The best run for every level and seed that has been played.
+    best: Vec<RunRecord>,
+    /// The most recent runs, newest first.
+    history: Vec<RunRecord>,
+}
+
+impl HighScores {
+    fn load() -> Self {
+        persistence::load(HIGH_SCORES_KEY).unwrap_or_default()
+    }
+
+    fn save(&self) {
+        persistence::save(HIGH_SCORES_KEY, self);
+    }
+
+    pub fn best_for(&self, level: LevelKey, seed: u64) -> Option<&RunRecord> {
+        self.best
+            .iter()
+            .find(|record| record.level == level && record.seed == seed)
+    }
+
+    /// The highest scoring runs across every level and seed, best first.
+    pub fn top(&self, count: usize) -> Vec<&RunRecord> {
+        let mut runs: Vec<_> = self.best.iter().collect();
+        runs.sort_by_key(|run| std::cmp::Reverse(run.score));
+        runs.truncate(count);
+        runs
+    }
+
+    /// The most recent runs, newest first.
+    pub fn history(&self) -> &[RunRecord] {
+        &self.history
+    }
+
+    /// Add a finished run, returning whether it beat the previous best for its level and seed.
+    fn record(&mut self, run: RunRecord) -> bool {
+        self.history.insert(0, run.clone());
+        self.history.truncate(HISTORY_LENGTH);
+
+        let previous = self
+            .best
+            .iter_mut()
+            .find(|record| record.level == run.level && record.seed == run.seed);
+        match previous {
+            Some(previous) if previous.score >= run.score => false,
+            Some(previous) => {
+                *previous = run;
+                true
+            }
+            None => {
+                self.best.push(run);
+                true
+            }
+        }
+    }
+}
+
+/// The run that ended most recently, for the end of run screens.
+#[derive(Resource, Debug, Default)]
+pub struct LatestRun {
+    pub record: Option<RunRecord>,
+    pub new_best: bool,
+}
+
+/// Everything needed to record the run that just ended.
+#[derive(SystemParam)]
+struct RunRecorder<'w> {
+    current_level: Res<'w, CurrentLevel>,
+    game_rng: Res<'w, GameRng>,
+    ledger: Res<'w, Ledger>,
+    clock: Res<'w, GameClock>,
+    high_scores: ResMut<'w, HighScores>,
+    latest_run: ResMut<'w, LatestRun>,
+}
+
+impl RunRecorder<'_> {
+    fn record(&mut self, completed: bool) {
+        let record = RunRecord {
+            level: self.current_level.0,
+            seed: self.game_rng.seed,
+            date: Date::today().to_string(),
+            duration: self.clock.elapsed,
+            score: self.ledger.score,
+            deliveries: self.ledger.fulfilled,
+            failures: self.ledger.failed,
+            money: self.ledger.money,
+            completed,
+        };
+        self.latest_run.new_best = self.high_scores.record(record.clone());
+        self.latest_run.record = Some(record);
+        self.high_scores.save();
+    }
+}
+
+fn record_completed_run(_trigger: Trigger<LevelCompleted>, mut recorder: RunRecorder) {
+    recorder.record(true);
+}
+
+fn record_failed_run(_trigger: Trigger<LevelFailed>, mut recorder: RunRecorder) {
+    recorder.record(false);
+}
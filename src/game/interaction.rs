@@ -1,14 +1,13 @@
-use bevy::{
-    color::palettes::css::{DARK_RED, RED, WHITE},
-    prelude::*,
-    window::PrimaryWindow,
-};
+use bevy::{prelude::*, window::PrimaryWindow};
 
-use crate::AppSet;
+use crate::{screen::Menu, AppSet};
 
 use super::{
     assets::SfxKey,
     audio::sfx::PlaySfx,
+    editor::editing,
+    palette::GamePalette,
+    resource::{PinnedConsumer, ResourceConsumer},
     spawn::{
         connection::{
             ConnectionAnchor, ConnectionProperties, ConnectionTarget, ConnectionUnderConstruction,
@@ -16,6 +15,7 @@ use super::{
         },
         planet::{OrbitalPosition, SatelliteProperties},
     },
+    station::building_station,
 };
 
 #[derive(Resource, Default)]
@@ -27,6 +27,34 @@ impl MousePosition {
     }
 }
 
+/// Limits what the player can do with the mouse, so the tutorial can walk through one action at a time.
+/// Everything is allowed by default.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct InteractionGate {
+    /// If set, only these satellites can start or finish a connection.
+    pub satellites: Option<Vec<Entity>>,
+    /// Whether clicking a connection removes it.
+    pub remove_connections: bool,
+}
+
+impl Default for InteractionGate {
+    fn default() -> Self {
+        Self {
+            satellites: None,
+            remove_connections: true,
+        }
+    }
+}
+
+impl InteractionGate {
+    pub fn allows_satellite(&self, satellite: Entity) -> bool {
+        match &self.satellites {
+            Some(satellites) => satellites.contains(&satellite),
+            None => true,
+        }
+    }
+}
+
 #[derive(Component, Copy, Clone, Eq, PartialEq, Debug, Reflect)]
 #[reflect(Component, Default, PartialEq)]
 pub enum InteractionState {
@@ -47,6 +75,7 @@ impl InteractionState {
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<MousePosition>();
+    app.init_resource::<InteractionGate>();
     app.add_systems(
         Update,
         (
@@ -55,15 +84,21 @@ pub(super) fn plugin(app: &mut App) {
             process_connection_interactions,
         )
             .chain()
-            .in_set(AppSet::RecordInput),
+            .in_set(AppSet::RecordInput)
+            .run_if(in_state(Menu::None)),
     );
     app.add_systems(
         Update,
         (
             handle_interaction,
             play_interaction_sfx,
-            spawn_connections,
-            remove_connections,
+            // The sandbox editor uses the mouse for its own tools.
+            (
+                spawn_connections.run_if(not(building_station)),
+                remove_connections,
+                pin_consumers,
+            )
+                .run_if(not(editing)),
         )
             .in_set(AppSet::Update),
     );
@@ -161,6 +196,7 @@ fn process_mouse(
 }
 
 fn handle_interaction(
+    palette: Res<GamePalette>,
     mut planet_query: Query<
         (&mut SatelliteProperties, &InteractionState),
         Changed<InteractionState>,
@@ -172,36 +208,43 @@ fn handle_interaction(
 ) {
     for (mut satellite_properties, interaction) in &mut planet_query {
         satellite_properties.color = match interaction {
-            InteractionState::Pressed => Color::Srgba(RED),
-            InteractionState::Hovered => Color::Srgba(DARK_RED),
-            InteractionState::None => Color::Srgba(WHITE),
+            InteractionState::Pressed => palette.pressed,
+            InteractionState::Hovered => palette.hovered,
+            InteractionState::None => palette.idle,
         };
     }
 
     for (mut connection_properties, interaction) in &mut connection_query {
         connection_properties.color = match interaction {
-            InteractionState::Pressed => Color::Srgba(RED),
-            InteractionState::Hovered => Color::Srgba(DARK_RED),
-            InteractionState::None => Color::Srgba(WHITE),
+            InteractionState::Pressed => palette.pressed,
+            InteractionState::Hovered => palette.hovered,
+            InteractionState::None => palette.idle,
         };
     }
 }
 
 fn play_interaction_sfx(
     mut commands: Commands,
-    planet_query: Query<&InteractionState, Changed<InteractionState>>,
+    planet_query: Query<(&InteractionState, Option<&OrbitalPosition>), Changed<InteractionState>>,
 ) {
-    for satllite_interaction in &planet_query {
-        match satllite_interaction {
-            InteractionState::Hovered => commands.trigger(PlaySfx::Key(SfxKey::ButtonHover)),
-            InteractionState::Pressed => commands.trigger(PlaySfx::Key(SfxKey::ButtonPress)),
-            _ => (),
+    for (satllite_interaction, orbital_position) in &planet_query {
+        let key = match satllite_interaction {
+            InteractionState::Hovered => SfxKey::ButtonHover,
+            InteractionState::Pressed => SfxKey::ButtonPress,
+            _ => continue,
+        };
+
+        // Satellites sound from where they are, everything else plays globally.
+        match orbital_position {
+            Some(position) => commands.trigger(PlaySfx::At(key, position.get_euclidean_position())),
+            None => commands.trigger(PlaySfx::Key(key)),
         }
     }
 }
 
-fn spawn_connections(
+pub fn spawn_connections(
     mut commands: Commands,
+    gate: Res<InteractionGate>,
     mut connection_query: Query<
         (Entity, &mut ConnectionTarget, &ConnectionAnchor),
         With<ConnectionUnderConstruction>,
@@ -212,7 +255,7 @@ fn spawn_connections(
     >,
 ) {
     for (entity, interaction) in &satellite_query {
-        if *interaction == InteractionState::Pressed {
+        if *interaction == InteractionState::Pressed && gate.allows_satellite(entity) {
             if connection_query.is_empty() {
                 commands.trigger(InitiateConnection(entity));
             } else if let Ok((connection, mut target, anchor)) = connection_query.get_single_mut() {
@@ -231,14 +274,42 @@ fn spawn_connections(
 
 fn remove_connections(
     mut commands: Commands,
+    gate: Res<InteractionGate>,
     connection_query: Query<
         (Entity, &InteractionState),
         (With<ConnectionProperties>, Changed<InteractionState>),
     >,
 ) {
+    if !gate.remove_connections {
+        return;
+    }
+
     for (entity, interaction) in &connection_query {
         if *interaction == InteractionState::Pressed {
             commands.entity(entity).despawn();
         }
     }
 }
+
+/// Right-clicking a consumer pins it, so its orders are served before anyone else's.
+/// Right-clicking it again unpins it.
+fn pin_consumers(
+    mut commands: Commands,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    gate: Res<InteractionGate>,
+    consumer_query: Query<(Entity, &InteractionState, Has<PinnedConsumer>), With<ResourceConsumer>>,
+) {
+    if !mouse_button.just_pressed(MouseButton::Right) {
+        return;
+    }
+
+    for (entity, interaction, pinned) in &consumer_query {
+        if *interaction != InteractionState::None && gate.allows_satellite(entity) {
+            if pinned {
+                commands.entity(entity).remove::<PinnedConsumer>();
+            } else {
+                commands.entity(entity).insert(PinnedConsumer);
+            }
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Keep the books: money earned from deliveries and the tally of orders.
+
+use bevy::prelude::*;
+
+use super::{
+    assets::SfxKey,
+    audio::{sfx::PlaySfx, soundtrack::DuckMusic},
+    clock::GameClock,
+    market::MarketPrices,
+    resource::{GameResource, OrderPriority},
+    spawn::{
+        connection::{
+            connection_breaks, connection_ends, connection_length, ConnectionAnchor,
+            ConnectionProperties, ConnectionTarget, ConnectionUnderConstruction,
+        },
+        level::Sun,
+        planet::OrbitalPosition,
+    },
+};
+use crate::{screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Ledger>();
+    app.init_resource::<Ledger>();
+
+    app.init_resource::<UpkeepSchedule>();
+
+    app.add_systems(
+        OnEnter(Screen::Playing),
+        (reset_ledger, reset_upkeep_schedule),
+    );
+    app.add_systems(
+        Update,
+        (charge_for_construction, charge_upkeep)
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+    app.observe(record_fulfilled_order);
+    app.observe(record_failed_order);
+    app.observe(record_lost_cargo);
+}
+
+/// Money paid out for every order that is delivered, before market prices move it.
+pub const DELIVERY_PAYOUT: i32 = 100;
+
+/// Money deducted for every order that expires.
+pub const FAILURE_PENALTY: i32 = 50;
+
+/// Money deducted for every piece of cargo thrown away because there was no room for it.
+pub const CARGO_LOST_PENALTY: i32 = 20;
+
+/// Money it costs to build a connection, for every unit of its length.
+const CONSTRUCTION_COST_PER_UNIT: f32 = 0.25;
+
+/// Money every connection costs each cycle, for every unit of its length at the time.
+const UPKEEP_COST_PER_UNIT: f32 = 0.1;
+
+/// What building a connection of this length costs.
+pub fn construction_cost(length: f32) -> i32 {
+    (length * CONSTRUCTION_COST_PER_UNIT).round() as i32
+}
+
+/// What keeping a connection of this length costs each cycle.
+pub fn upkeep_cost(length: f32) -> i32 {
+    (length * UPKEEP_COST_PER_UNIT).round() as i32
+}
+
+/// How long the music stays lowered so a failure alert can be heard.
+const FAILURE_ALERT_SECS: f32 = 1.5;
+
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct Ledger {
+    pub money: i32,
+    pub score: u32,
+    pub fulfilled: u32,
+    pub failed: u32,
+}
+
+/// Triggered when cargo arrives at the satellite that demanded it.
+#[derive(Event, Debug)]
+pub struct OrderFulfilled {
+    /// The satellite that placed the order.
+    pub satellite: Entity,
+    /// The cargo that was delivered, which is paid for at the satellite's market price.
+    pub resource: GameResource,
+    /// How urgent the order was, which multiplies the payout.
+    pub priority: OrderPriority,
+}
+
+impl OrderFulfilled {
+    /// What the delivery pays, at the satellite's market price and by the order's priority.
+    pub fn payout(&self, market_query: &Query<&MarketPrices>) -> i32 {
+        let price = market_query
+            .get(self.satellite)
+            .map_or(DELIVERY_PAYOUT, |market| market.price(self.resource));
+        self.priority.payout(price)
+    }
+}
+
+/// Triggered when a demand expires before any cargo was sent for it.
+#[derive(Event, Debug)]
+pub struct OrderFailed {
+    /// The satellite that placed the order.
+    pub satellite: Entity,
+}
+
+/// Triggered when cargo is thrown away because the satellite it stopped at was full.
+#[derive(Event, Debug)]
+pub struct CargoLost;
+
+/// The last cycle that upkeep was paid for.
+#[derive(Resource, Debug, Default)]
+struct UpkeepSchedule {
+    cycle: u32,
+}
+
+fn reset_ledger(mut ledger: ResMut<Ledger>) {
+    *ledger = Ledger::default();
+}
+
+fn reset_upkeep_schedule(mut schedule: ResMut<UpkeepSchedule>) {
+    *schedule = UpkeepSchedule::default();
+}
+
+/// Connections are paid for once they are established. Ones that break straight away,
+/// for being too long or crossing the sun, cost nothing, whether or not they are gone yet.
+fn charge_for_construction(
+    mut established: RemovedComponents<ConnectionUnderConstruction>,
+    mut ledger: ResMut<Ledger>,
+    sun: Res<Sun>,
+    connection_query: Query<(&ConnectionAnchor, &ConnectionTarget, &ConnectionProperties)>,
+    position_query: Query<&OrbitalPosition>,
+) {
+    for connection in established.read() {
+        let Ok((anchor, target, properties)) = connection_query.get(connection) else {
+            continue;
+        };
+        let Some((start, end)) = connection_ends(anchor, target, &position_query) else {
+            continue;
+        };
+        if !connection_breaks(properties, start, end, &sun) {
+            ledger.money -= construction_cost(start.distance(end));
+        }
+    }
+}
+
+/// Every connection costs upkeep at the end of each cycle, by how long it is at that moment.
+fn charge_upkeep(
+    clock: Res<GameClock>,
+    mut schedule: ResMut<UpkeepSchedule>,
+    mut ledger: ResMut<Ledger>,
+    connection_query: Query<
+        (&ConnectionAnchor, &ConnectionTarget),
+        Without<ConnectionUnderConstruction>,
+    >,
+    position_query: Query<&OrbitalPosition>,
+) {
+    if clock.cycle() == schedule.cycle {
+        return;
+    }
+    schedule.cycle = clock.cycle();
+
+    ledger.money -= connection_query
+        .iter()
+        .filter_map(|(anchor, target)| connection_length(anchor, target, &position_query))
+        .map(upkeep_cost)
+        .sum::<i32>();
+}
+
+fn record_fulfilled_order(
+    trigger: Trigger<OrderFulfilled>,
+    mut ledger: ResMut<Ledger>,
+    market_query: Query<&MarketPrices>,
+) {
+    let payout = trigger.event().payout(&market_query);
+    ledger.money += payout;
+    ledger.score += payout as u32;
+    ledger.fulfilled += 1;
+}
+
+fn record_failed_order(
+    _trigger: Trigger<OrderFailed>,
+    mut commands: Commands,
+    mut ledger: ResMut<Ledger>,
+) {
+    ledger.money -= FAILURE_PENALTY;
+    ledger.failed += 1;
+
+    commands.trigger(PlaySfx::Key(SfxKey::Alert));
+    commands.trigger(DuckMusic {
+        duration: FAILURE_ALERT_SECS,
+    });
+}
+
+fn record_lost_cargo(_trigger: Trigger<CargoLost>, mut ledger: ResMut<Ledger>) {
+    ledger.money -= CARGO_LOST_PENALTY;
+}
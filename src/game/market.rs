@@ -0,0 +1,141 @@
+//! Market prices at every consumer. A delivery pays more the further the consumer is from
+//! the nearest producer and the scarcer the cargo is, and less while the consumer
+//! is still stocked up from recent deliveries.
+
+use bevy::{prelude::*, utils::HashMap};
+
+use super::{
+    ledger::{OrderFulfilled, DELIVERY_PAYOUT},
+    resource::{
+        GameResource, GameResourceDemand, GameResourceInStorage, Refinery, ResourceConsumer,
+        ResourceSpawner,
+    },
+    spawn::planet::OrbitalPosition,
+};
+use crate::{screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(record_market_delivery);
+    app.add_systems(
+        Update,
+        (add_market_prices, update_market_prices)
+            .chain()
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// A consumer this far from the nearest producer pays the base price.
+const REFERENCE_DISTANCE: f32 = 150.0;
+
+/// Every unit of distance to the nearest producer beyond the reference distance adds this
+/// fraction of the base price. Closer consumers pay less, down to half.
+const DISTANCE_PRICE_PER_UNIT: f32 = 1.0 / 300.0;
+
+/// The distance charged for cargo that nothing produces.
+const UNSUPPLIED_DISTANCE: f32 = 450.0;
+
+/// Every open order beyond the cargo in storage raises the price by this fraction, and the other way around.
+const SCARCITY_PRICE_STEP: f32 = 0.05;
+
+/// How far supply and demand can move the price, as a fraction of what it would be otherwise.
+const SCARCITY_RANGE: (f32, f32) = (0.75, 1.5);
+
+/// Every recent delivery of the same cargo lowers the price by about this fraction.
+const DELIVERY_SATURATION_STEP: f32 = 0.15;
+
+/// Seconds for a consumer to forget half of its recent deliveries.
+const SATURATION_HALF_LIFE_SECS: f32 = 30.0;
+
+/// What each kind of cargo pays when delivered to this consumer, before the order's priority.
+#[derive(Component, Debug, Default)]
+pub struct MarketPrices {
+    prices: HashMap<GameResource, i32>,
+    /// How stocked up the consumer is from recent deliveries, by kind of cargo.
+    saturation: HashMap<GameResource, f32>,
+}
+
+impl MarketPrices {
+    pub fn price(&self, resource: GameResource) -> i32 {
+        self.prices
+            .get(&resource)
+            .copied()
+            .unwrap_or(DELIVERY_PAYOUT)
+    }
+}
+
+fn add_market_prices(
+    mut commands: Commands,
+    consumer_query: Query<Entity, (With<ResourceConsumer>, Without<MarketPrices>)>,
+) {
+    for consumer in &consumer_query {
+        commands.entity(consumer).insert(MarketPrices::default());
+    }
+}
+
+fn update_market_prices(
+    time: Res<Time>,
+    mut consumer_query: Query<(&OrbitalPosition, &mut MarketPrices), With<ResourceConsumer>>,
+    spawner_query: Query<(&OrbitalPosition, &ResourceSpawner)>,
+    refinery_query: Query<(&OrbitalPosition, &Refinery)>,
+    storage_query: Query<&GameResource, With<GameResourceInStorage>>,
+    demand_query: Query<(&GameResource, &GameResourceDemand)>,
+) {
+    // Refineries count as producers of what they refine into.
+    let producers: Vec<_> = spawner_query
+        .iter()
+        .map(|(orbit, spawner)| (orbit.get_euclidean_position(), spawner.spawn_type))
+        .chain(
+            refinery_query
+                .iter()
+                .map(|(orbit, refinery)| (orbit.get_euclidean_position(), refinery.0.output)),
+        )
+        .collect();
+
+    // Supply and demand are counted across the whole system, so every consumer shares them.
+    let mut balance: HashMap<GameResource, f32> = HashMap::new();
+    for resource in &storage_query {
+        *balance.entry(*resource).or_default() -= 1.0;
+    }
+    for (resource, demand) in &demand_query {
+        if demand.claim.is_none() {
+            *balance.entry(*resource).or_default() += 1.0;
+        }
+    }
+
+    let decay = 0.5_f32.powf(time.delta_seconds() / SATURATION_HALF_LIFE_SECS);
+    for (orbit, mut market) in &mut consumer_query {
+        let position = orbit.get_euclidean_position();
+        for resource in GameResource::ALL {
+            let distance = producers
+                .iter()
+                .filter(|(_, produced)| *produced == resource)
+                .map(|(producer, _)| producer.distance(position))
+                .min_by(f32::total_cmp)
+                .unwrap_or(UNSUPPLIED_DISTANCE);
+
+            let shortage = balance.get(&resource).copied().unwrap_or_default();
+            let scarcity =
+                (1.0 + shortage * SCARCITY_PRICE_STEP).clamp(SCARCITY_RANGE.0, SCARCITY_RANGE.1);
+
+            let saturation = market.saturation.entry(resource).or_default();
+            *saturation *= decay;
+            let saturation = 1.0 + *saturation * DELIVERY_SATURATION_STEP;
+
+            let remoteness =
+                (1.0 + (distance - REFERENCE_DISTANCE) * DISTANCE_PRICE_PER_UNIT).max(0.5);
+            let price = DELIVERY_PAYOUT as f32 * remoteness * scarcity / saturation;
+            market.prices.insert(resource, price.round() as i32);
+        }
+    }
+}
+
+fn record_market_delivery(
+    trigger: Trigger<OrderFulfilled>,
+    mut market_query: Query<&mut MarketPrices>,
+) {
+    let event = trigger.event();
+    if let Ok(mut market) = market_query.get_mut(event.satellite) {
+        *market.saturation.entry(event.resource).or_default() += 1.0;
+    }
+}
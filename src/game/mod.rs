@@ -2,24 +2,62 @@
 
 use bevy::prelude::*;
 
+pub mod alignment;
 mod animation;
 pub mod assets;
 pub mod audio;
-mod interaction;
+pub mod campaign;
+pub mod clock;
+pub mod daily;
+pub mod director;
+pub mod editor;
+pub mod high_scores;
+pub mod interaction;
+pub mod ledger;
+pub mod market;
 mod movement;
+pub mod objective;
+pub mod palette;
 pub mod rendering;
+pub mod research;
 pub mod resource;
+pub mod rng;
 pub mod spawn;
+pub mod station;
+pub mod statistics;
+pub mod system_event;
+pub mod tutorial;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
         animation::plugin,
         audio::plugin,
         assets::plugin,
+        clock::plugin,
+        ledger::plugin,
         movement::plugin,
+        palette::plugin,
         spawn::plugin,
         rendering::plugin,
         interaction::plugin,
         resource::plugin,
+        rng::plugin,
+    ));
+
+    // Progress through a run and what is kept once it ends.
+    app.add_plugins((
+        campaign::plugin,
+        objective::plugin,
+        statistics::plugin,
+        high_scores::plugin,
+        daily::plugin,
+        director::plugin,
+        editor::plugin,
+        tutorial::plugin,
+        system_event::plugin,
+        alignment::plugin,
+        station::plugin,
+        market::plugin,
+        research::plugin,
     ));
 }
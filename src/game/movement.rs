@@ -75,7 +75,7 @@ pub struct MovementController(pub Vec2);
 //     }
 // }
 
-fn apply_orbital_movement(
+pub(super) fn apply_orbital_movement(
     time: Res<Time>,
     mut movement_query: Query<(&OrbitalMovement, &mut OrbitalPosition)>,
 ) {
@@ -0,0 +1,235 @@
+//! Win and loss conditions that a level sets for the player.
+
+use std::collections::VecDeque;
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    assets::{HandleMap, LevelKey},
+    campaign::CurrentLevel,
+    clock::GameClock,
+    ledger::{Ledger, OrderFulfilled},
+    spawn::{
+        connection::{ConnectionAnchor, ConnectionTarget, ConnectionUnderConstruction},
+        level::Level,
+        planet::Planet,
+    },
+};
+use crate::{screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ObjectiveProgress>();
+    app.register_type::<DeliveryRate>();
+    app.init_resource::<DeliveryRate>();
+
+    app.add_systems(OnEnter(Screen::Playing), reset_objective_progress);
+    app.observe(record_delivery_rate);
+    app.add_systems(
+        Update,
+        (update_objective_progress, check_objectives)
+            .chain()
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// A goal listed in a level file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Objective {
+    /// Deliver this many orders.
+    Deliver(u32),
+    /// Deliver this many orders within a single cycle.
+    Throughput(u32),
+    /// Keep the network running until this many cycles have passed.
+    Survive(u32),
+    /// Link every planet into one network.
+    ConnectAll,
+    /// The level is lost once this many orders have failed.
+    FailuresBelow(u32),
+}
+
+impl Objective {
+    pub fn label(&self) -> String {
+        match self {
+            Objective::Deliver(count) => format!("Deliver {count} orders"),
+            Objective::Throughput(count) => format!("Deliver {count} in one cycle"),
+            Objective::Survive(cycles) => format!("Survive {cycles} cycles"),
+            Objective::ConnectAll => "Connect every planet".to_string(),
+            Objective::FailuresBelow(count) => format!("Fail fewer than {count}"),
+        }
+    }
+
+    /// Whether this objective only decides when a level is lost.
+    pub fn is_loss_condition(&self) -> bool {
+        matches!(self, Objective::FailuresBelow(_))
+    }
+}
+
+/// How far the player is towards one objective of the current level.
+#[derive(Debug, Clone, Copy)]
+pub struct ObjectiveState {
+    pub objective: Objective,
+    pub current: u32,
+    pub target: u32,
+}
+
+impl ObjectiveState {
+    pub fn is_met(&self) -> bool {
+        match self.objective {
+            Objective::FailuresBelow(_) => self.current < self.target,
+            _ => self.current >= self.target,
+        }
+    }
+
+    /// Whether this objective can no longer be met.
+    pub fn is_lost(&self) -> bool {
+        match self.objective {
+            Objective::FailuresBelow(_) => self.current >= self.target,
+            _ => false,
+        }
+    }
+}
+
+/// The objectives of the current level and how far along each one is.
+#[derive(Resource, Debug, Default)]
+pub struct ObjectiveProgress(pub Vec<ObjectiveState>);
+
+/// Deliveries counted per cycle, for [`Objective::Throughput`].
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+struct DeliveryRate {
+    cycle: u32,
+    this_cycle: u32,
+    best_cycle: u32,
+}
+
+/// Triggered once every objective of the current level is met.
+#[derive(Event, Debug)]
+pub struct LevelCompleted;
+
+/// Triggered when an objective of the current level can no longer be met.
+#[derive(Event, Debug)]
+pub struct LevelFailed;
+
+fn reset_objective_progress(
+    mut progress: ResMut<ObjectiveProgress>,
+    mut delivery_rate: ResMut<DeliveryRate>,
+) {
+    *progress = ObjectiveProgress::default();
+    *delivery_rate = DeliveryRate::default();
+}
+
+fn record_delivery_rate(
+    _trigger: Trigger<OrderFulfilled>,
+    clock: Res<GameClock>,
+    mut delivery_rate: ResMut<DeliveryRate>,
+) {
+    if delivery_rate.cycle != clock.cycle() {
+        delivery_rate.cycle = clock.cycle();
+        delivery_rate.this_cycle = 0;
+    }
+    delivery_rate.this_cycle += 1;
+    delivery_rate.best_cycle = delivery_rate.best_cycle.max(delivery_rate.this_cycle);
+}
+
+fn update_objective_progress(
+    current_level: Res<CurrentLevel>,
+    level_handles: Res<HandleMap<LevelKey>>,
+    levels: Res<Assets<Level>>,
+    ledger: Res<Ledger>,
+    clock: Res<GameClock>,
+    delivery_rate: Res<DeliveryRate>,
+    planet_query: Query<Entity, With<Planet>>,
+    connection_query: Query<
+        (&ConnectionAnchor, &ConnectionTarget),
+        Without<ConnectionUnderConstruction>,
+    >,
+    mut progress: ResMut<ObjectiveProgress>,
+) {
+    let Some(level) = levels.get(&level_handles[&current_level.0]) else {
+        return;
+    };
+
+    progress.0 = level
+        .objectives
+        .iter()
+        .map(|objective| {
+            let (current, target) = match *objective {
+                Objective::Deliver(count) => (ledger.fulfilled, count),
+                Objective::Throughput(count) => (delivery_rate.best_cycle, count),
+                Objective::Survive(cycles) => (clock.cycle(), cycles),
+                Objective::ConnectAll => (
+                    largest_network(&planet_query, &connection_query),
+                    planet_query.iter().count() as u32,
+                ),
+                Objective::FailuresBelow(count) => (ledger.failed, count),
+            };
+            ObjectiveState {
+                objective: *objective,
+                current,
+                target,
+            }
+        })
+        .collect();
+}
+
+/// How many planets the largest network links together. Networks can pass through
+/// satellites that aren't planets, like relay stations.
+fn largest_network(
+    planet_query: &Query<Entity, With<Planet>>,
+    connection_query: &Query<
+        (&ConnectionAnchor, &ConnectionTarget),
+        Without<ConnectionUnderConstruction>,
+    >,
+) -> u32 {
+    let mut links: HashMap<Entity, Vec<Entity>> = HashMap::new();
+    for (anchor, target) in connection_query {
+        if let ConnectionTarget::Satellite(target) = *target {
+            links.entry(anchor.satellite).or_default().push(target);
+            links.entry(target).or_default().push(anchor.satellite);
+        }
+    }
+
+    let mut visited = HashSet::new();
+    let mut largest = 0;
+    for planet in planet_query {
+        if !visited.insert(planet) {
+            continue;
+        }
+
+        let mut size = 0;
+        let mut open_list = VecDeque::from([planet]);
+        while let Some(current) = open_list.pop_front() {
+            if planet_query.contains(current) {
+                size += 1;
+            }
+            for next in links.get(&current).into_iter().flatten() {
+                if visited.insert(*next) {
+                    open_list.push_back(*next);
+                }
+            }
+        }
+        largest = largest.max(size);
+    }
+    largest
+}
+
+fn check_objectives(mut commands: Commands, progress: Res<ObjectiveProgress>) {
+    if progress.0.iter().any(ObjectiveState::is_lost) {
+        commands.trigger(LevelFailed);
+        return;
+    }
+
+    // Levels without any goals, like endless mode, run until they're lost or the player leaves.
+    let has_goals = progress
+        .0
+        .iter()
+        .any(|state| !state.objective.is_loss_condition());
+    if has_goals && progress.0.iter().all(ObjectiveState::is_met) {
+        commands.trigger(LevelCompleted);
+    }
+}
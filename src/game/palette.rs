@@ -0,0 +1,80 @@
+//! Colours used to draw the game board, switchable for colour-blind players.
+
+use bevy::{
+    color::palettes::css::{
+        CRIMSON, DARK_ORANGE, DARK_RED, DARK_SALMON, GOLD, ORANGE_RED, RED, SADDLE_BROWN, WHITE,
+    },
+    prelude::*,
+};
+
+use crate::settings::Settings;
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(GamePalette::STANDARD);
+    app.add_systems(
+        Update,
+        select_game_palette.run_if(resource_changed::<Settings>),
+    );
+}
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct GamePalette {
+    pub idle: Color,
+    pub hovered: Color,
+    pub pressed: Color,
+    /// Connections blend towards this colour as they approach their maximum range.
+    pub out_of_range: Color,
+    pub range_guide: Color,
+    pub demand: Color,
+    pub express: Color,
+    pub critical: Color,
+    /// The ring around consumers the player pinned as high priority.
+    pub pinned: Color,
+    /// The ring around satellites with no room left in their storage.
+    pub full: Color,
+    /// Perishable cargo fades towards this colour as it ages.
+    pub spoiled: Color,
+}
+
+impl GamePalette {
+    pub const STANDARD: Self = Self {
+        idle: Color::Srgba(WHITE),
+        hovered: Color::Srgba(DARK_RED),
+        pressed: Color::Srgba(RED),
+        out_of_range: Color::Srgba(RED),
+        range_guide: Color::Srgba(DARK_ORANGE),
+        demand: Color::Srgba(DARK_SALMON),
+        express: Color::Srgba(GOLD),
+        critical: Color::Srgba(CRIMSON),
+        pinned: Color::Srgba(GOLD),
+        full: Color::Srgba(ORANGE_RED),
+        spoiled: Color::Srgba(SADDLE_BROWN),
+    };
+
+    /// Based on the Okabe-Ito palette, which stays distinguishable
+    /// for the common forms of colour blindness.
+    pub const COLOUR_BLIND: Self = Self {
+        idle: Color::Srgba(WHITE),
+        hovered: Color::srgb(0.337, 0.706, 0.914),
+        pressed: Color::srgb(0.0, 0.447, 0.698),
+        out_of_range: Color::srgb(0.835, 0.369, 0.0),
+        range_guide: Color::srgb(0.902, 0.624, 0.0),
+        demand: Color::srgb(0.8, 0.475, 0.655),
+        express: Color::srgb(0.941, 0.894, 0.259),
+        critical: Color::srgb(0.835, 0.369, 0.0),
+        pinned: Color::srgb(0.941, 0.894, 0.259),
+        full: Color::srgb(0.835, 0.369, 0.0),
+        spoiled: Color::srgb(0.337, 0.706, 0.914),
+    };
+}
+
+fn select_game_palette(settings: Res<Settings>, mut palette: ResMut<GamePalette>) {
+    let selected = if settings.colour_blind {
+        GamePalette::COLOUR_BLIND
+    } else {
+        GamePalette::STANDARD
+    };
+    if *palette != selected {
+        *palette = selected;
+    }
+}
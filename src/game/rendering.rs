@@ -1,30 +1,34 @@
 use std::f32::consts::PI;
 
-use bevy::{
-    color::palettes::css::{DARK_ORANGE, DARK_SALMON, WHITE},
-    ecs::query::QueryEntityError,
-    prelude::*,
-};
+use bevy::{color::palettes::css::WHITE, ecs::query::QueryEntityError, prelude::*};
 use bevy_vector_shapes::{
     prelude::ShapePainter,
     shapes::{Cap, DiscPainter, LinePainter, RegularPolygonPainter},
 };
 
-use crate::AppSet;
+use crate::{screen::Screen, AppSet};
 
 use super::{
     interaction::InteractionState,
-    resource::{GameResourceDemand, GameResourceInTransit, PendingDeparture, ResourceContainer},
+    ledger::{construction_cost, upkeep_cost},
+    palette::GamePalette,
+    research::{ResearchModifiers, SelectedLink},
+    resource::{
+        Age, GameResource, GameResourceDemand, GameResourceInStorage, GameResourceInTransit,
+        OrderPriority, PendingDeparture, PinnedConsumer, ResourceContainer, WaitingForRoom,
+        Warehouse,
+    },
     spawn::{
         connection::{
             ConnectionAnchor, ConnectionConfig, ConnectionProperties, ConnectionTarget,
-            ConnectionUnderConstruction,
+            ConnectionUnderConstruction, LinkKind,
         },
         planet::{OrbitalPosition, Planet, SatelliteProperties},
     },
 };
 
 pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Playing), spawn_construction_cost_label);
     app.add_systems(
         Update,
         (
@@ -61,18 +65,38 @@ fn render_orbits(
     }
 }
 
+/// How far outside a satellite its full ring and warehouse outline are drawn.
+const STORAGE_MARGIN: f32 = 3.0;
+
 fn render_satellites(
     mut painter: ShapePainter,
-    planet_query: Query<(&Planet, &OrbitalPosition, &SatelliteProperties)>,
+    palette: Res<GamePalette>,
+    planet_query: Query<(
+        &OrbitalPosition,
+        &SatelliteProperties,
+        Option<&ResourceContainer>,
+        Has<Warehouse>,
+    )>,
 ) {
-    painter.hollow = false;
-
-    for (_, orbital_position, satellite_properties) in &planet_query {
+    for (orbital_position, satellite_properties, container, warehouse) in &planet_query {
         let position = orbital_position.get_euclidean_position();
 
         painter.set_translation(position);
+        painter.hollow = false;
         painter.set_color(satellite_properties.color);
         painter.circle(satellite_properties.radius);
+
+        painter.hollow = true;
+        painter.thickness = 1.0;
+        if warehouse {
+            painter.roundness = 0.1;
+            painter.ngon(4.0, satellite_properties.radius + STORAGE_MARGIN * 2.0);
+        }
+        let full = container.is_some_and(|container| !container.has_room());
+        if full {
+            painter.set_color(palette.full);
+            painter.circle(satellite_properties.radius + STORAGE_MARGIN);
+        }
     }
 
     painter.set_translation(Vec3::ZERO);
@@ -80,22 +104,26 @@ fn render_satellites(
 
 fn render_connections(
     mut painter: ShapePainter,
-    connection_query: Query<(&ConnectionAnchor, &ConnectionTarget, &ConnectionProperties)>,
-    planet_query: Query<(&Planet, &OrbitalPosition, &SatelliteProperties)>,
+    palette: Res<GamePalette>,
+    connection_query: Query<(
+        &ConnectionAnchor,
+        &ConnectionTarget,
+        &ConnectionProperties,
+        &LinkKind,
+    )>,
+    planet_query: Query<(&OrbitalPosition, &SatelliteProperties)>,
 ) {
-    painter.thickness = 0.5;
-
     fn get_position_from_planet(
         entity: Entity,
-        planet_query: &Query<(&Planet, &OrbitalPosition, &SatelliteProperties)>,
+        planet_query: &Query<(&OrbitalPosition, &SatelliteProperties)>,
     ) -> Result<Vec3, QueryEntityError> {
-        let (_, orbital_position, _properties) = planet_query.get(entity)?;
+        let (orbital_position, _properties) = planet_query.get(entity)?;
         let start = Vec3::Y * orbital_position.radius;
         let rotation = Quat::from_rotation_z(-orbital_position.position);
         Ok(rotation * start)
     }
 
-    for (connection_anchor, connection_target, connection_properties) in &connection_query {
+    for (connection_anchor, connection_target, connection_properties, kind) in &connection_query {
         if let Ok(start) = get_position_from_planet(connection_anchor.satellite, &planet_query) {
             let end = match connection_target {
                 ConnectionTarget::Satellite(target) => {
@@ -111,40 +139,127 @@ fn render_connections(
             let v = (distance - (connection_properties.range * 0.75))
                 .clamp(0.0, connection_properties.range * 0.25);
             let nv = (v / (connection_properties.range * 0.25)).clamp(0.0, 1.0);
-            let color = Color::srgb(1.0, 1.0 - nv, 1.0 - nv);
+            let color = palette.idle.mix(&palette.out_of_range, nv);
 
+            // Express links are drawn thin and long-haul links thick.
+            painter.thickness = match kind {
+                LinkKind::Standard => 0.5,
+                LinkKind::Express => 0.25,
+                LinkKind::LongHaul => 1.0,
+            };
             painter.set_color(color);
             painter.line(start, end);
         }
     }
 }
 
+/// Shows what the connection being dragged will cost, next to its loose end.
+#[derive(Component, Debug)]
+struct ConstructionCostLabel;
+
+/// How far the cost label sits from the end of the connection.
+const COST_LABEL_OFFSET: Vec3 = Vec3::new(24.0, 16.0, 0.0);
+
+fn spawn_construction_cost_label(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Construction Cost"),
+        ConstructionCostLabel,
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 16.0,
+                    ..default()
+                },
+            ),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        StateScoped(Screen::Playing),
+    ));
+}
+
 fn render_construction_range(
     mut painter: ShapePainter,
+    palette: Res<GamePalette>,
     connection_config: Res<ConnectionConfig>,
-    construction_query: Query<&ConnectionAnchor, With<ConnectionUnderConstruction>>,
-    planet_query: Query<&OrbitalPosition, With<Planet>>,
-    hover_planet_query: Query<(&OrbitalPosition, &InteractionState)>,
+    modifiers: Res<ResearchModifiers>,
+    selected_link: Res<SelectedLink>,
+    construction_query: Query<
+        (&ConnectionAnchor, &ConnectionTarget, &ConnectionProperties),
+        With<ConnectionUnderConstruction>,
+    >,
+    planet_query: Query<&OrbitalPosition>,
+    hover_planet_query: Query<(Entity, &OrbitalPosition, &InteractionState)>,
+    mut label_query: Query<
+        (&mut Text, &mut Transform, &mut Visibility),
+        With<ConstructionCostLabel>,
+    >,
 ) {
-    if let Ok(anchor) = construction_query.get_single() {
+    let mut preview = None;
+    if let Ok((anchor, target, properties)) = construction_query.get_single() {
         if let Ok(orbital_position) = planet_query.get(anchor.satellite) {
             painter.thickness = 1.0;
             painter.hollow = true;
-            painter.set_color(Color::Srgba(DARK_ORANGE));
+            painter.set_color(palette.range_guide);
             painter.set_translation(orbital_position.get_euclidean_position());
-            painter.circle(connection_config.range);
+            painter.circle(properties.range);
             painter.set_translation(Vec3::ZERO);
+
+            // The connection snaps to a hovered satellite, so that is what it would cost.
+            let hovered = hover_planet_query.iter().find(|(entity, _, interaction)| {
+                *entity != anchor.satellite && **interaction == InteractionState::Hovered
+            });
+            let end = match (hovered, target) {
+                (Some((_, position, _)), _) => position.get_euclidean_position(),
+                (None, ConnectionTarget::Position(position)) => *position,
+                (None, ConnectionTarget::Satellite(_)) => orbital_position.get_euclidean_position(),
+            };
+            preview = Some((
+                end,
+                orbital_position.get_euclidean_position().distance(end),
+                properties.range,
+            ));
+        }
+    }
+
+    for (mut text, mut transform, mut visibility) in &mut label_query {
+        let Some((end, length, range)) = preview else {
+            if *visibility != Visibility::Hidden {
+                *visibility = Visibility::Hidden;
+            }
+            continue;
+        };
+        if *visibility != Visibility::Inherited {
+            *visibility = Visibility::Inherited;
+        }
+        transform.translation = end + COST_LABEL_OFFSET;
+
+        let value = format!(
+            "${} + ${}/cycle",
+            construction_cost(length),
+            upkeep_cost(length)
+        );
+        let color = if length > range {
+            palette.out_of_range
+        } else {
+            palette.idle
+        };
+        if text.sections[0].value != value || text.sections[0].style.color != color {
+            text.sections[0].value = value;
+            text.sections[0].style.color = color;
         }
     }
 
     if construction_query.is_empty() {
-        for (orbital_position, interaction) in &hover_planet_query {
+        let range = modifiers.connection_range(&connection_config, selected_link.0);
+        for (_, orbital_position, interaction) in &hover_planet_query {
             if *interaction == InteractionState::Hovered {
                 painter.thickness = 1.0;
                 painter.hollow = true;
-                painter.set_color(Color::Srgba(DARK_ORANGE));
+                painter.set_color(palette.range_guide);
                 painter.set_translation(orbital_position.get_euclidean_position());
-                painter.circle(connection_config.range);
+                painter.circle(range);
                 painter.set_translation(Vec3::ZERO);
             }
         }
@@ -153,11 +268,19 @@ fn render_construction_range(
 
 const RESOURCE_RADIUS: f32 = 7.0;
 
+/// Cargo is white, perishable cargo fades as it ages.
+fn cargo_color(palette: &GamePalette, resource: GameResource, age: &Age) -> Color {
+    Color::Srgba(WHITE).mix(&palette.spoiled, 1.0 - age.freshness(resource))
+}
+
 fn render_resources(
     mut painter: ShapePainter,
-    planet_query: Query<(&OrbitalPosition, &SatelliteProperties, &ResourceContainer)>,
+    palette: Res<GamePalette>,
+    planet_query: Query<(Entity, &OrbitalPosition, &SatelliteProperties), With<ResourceContainer>>,
+    storage_query: Query<(&GameResource, &GameResourceInStorage, &Age)>,
+    waiting_query: Query<(&GameResource, &WaitingForRoom, &Age)>,
 ) {
-    for (position, properties, container) in &planet_query {
+    for (planet_entity, position, properties) in &planet_query {
         let pos = position.get_euclidean_position();
         let resource_pos = pos
             + Vec3::new(
@@ -169,24 +292,50 @@ fn render_resources(
         painter.set_translation(resource_pos);
         painter.roundness = 0.1;
         painter.hollow = false;
-        painter.set_color(Color::Srgba(WHITE));
 
-        // Todo need to do this by a resource query to get type, in the future when this matters
-        let count = container.storage_count;
-        for _ in 0..count {
-            painter.ngon(3.0, RESOURCE_RADIUS);
-            painter.translate(Vec3::Y * RESOURCE_RADIUS * 2.0);
+        for (resource, storage, age) in &storage_query {
+            if storage.satellite == planet_entity {
+                painter.set_color(cargo_color(&palette, *resource, age));
+                painter.ngon(resource.sides(), RESOURCE_RADIUS);
+                painter.translate(Vec3::Y * RESOURCE_RADIUS * 2.0);
+            }
+        }
+
+        // Cargo turned away for lack of room is outlined below the satellite.
+        painter.set_translation(pos - Vec3::Y * (properties.radius + RESOURCE_RADIUS * 2.0));
+        painter.hollow = true;
+        painter.thickness = 0.75;
+        for (resource, waiting, age) in &waiting_query {
+            if waiting.satellite == planet_entity {
+                painter.set_color(cargo_color(&palette, *resource, age));
+                painter.ngon(resource.sides(), RESOURCE_RADIUS);
+                painter.translate(Vec3::NEG_Y * RESOURCE_RADIUS * 2.0);
+            }
         }
     }
 }
 
+/// How far outside a pinned consumer its ring is drawn.
+const PIN_MARGIN: f32 = 6.0;
+
 fn render_demands(
     mut painter: ShapePainter,
+    palette: Res<GamePalette>,
     planet_query: Query<(&OrbitalPosition, &SatelliteProperties, Entity)>,
-    demand_query: Query<&GameResourceDemand>,
+    demand_query: Query<(&GameResource, &GameResourceDemand)>,
+    pinned_query: Query<(), With<PinnedConsumer>>,
 ) {
     for (position, properties, planet_entity) in &planet_query {
         let pos = position.get_euclidean_position();
+
+        if pinned_query.contains(planet_entity) {
+            painter.set_translation(pos);
+            painter.hollow = true;
+            painter.thickness = 1.0;
+            painter.set_color(palette.pinned);
+            painter.circle(properties.radius + PIN_MARGIN);
+        }
+
         let resource_pos = pos
             + Vec3::new(
                 properties.radius + RESOURCE_RADIUS,
@@ -198,11 +347,18 @@ fn render_demands(
         painter.roundness = 0.1;
         painter.thickness = 0.75;
         painter.hollow = true;
-        painter.set_color(Color::Srgba(DARK_SALMON));
 
-        for demand in demand_query.iter() {
+        for (resource, demand) in demand_query.iter() {
             if demand.satellite == planet_entity {
-                painter.ngon(3.0, RESOURCE_RADIUS);
+                // Urgent orders are filled in so they stand out from the outlines of normal ones.
+                let (color, filled) = match demand.priority {
+                    OrderPriority::Normal => (palette.demand, false),
+                    OrderPriority::Express => (palette.express, true),
+                    OrderPriority::Critical => (palette.critical, true),
+                };
+                painter.set_color(color);
+                painter.hollow = !filled;
+                painter.ngon(resource.sides(), RESOURCE_RADIUS);
                 painter.translate(Vec3::Y * RESOURCE_RADIUS * 2.0);
             }
         }
@@ -215,14 +371,17 @@ fn render_demands(
 
 fn render_transports(
     mut painter: ShapePainter,
-    transport_query: Query<&GameResourceInTransit, Without<PendingDeparture>>,
+    palette: Res<GamePalette>,
+    transport_query: Query<
+        (&GameResource, &GameResourceInTransit, &Age),
+        Without<PendingDeparture>,
+    >,
     planet_query: Query<&OrbitalPosition>,
 ) {
     painter.roundness = 0.1;
     painter.hollow = false;
-    painter.set_color(Color::Srgba(WHITE));
 
-    for transit in transport_query.iter() {
+    for (resource, transit, age) in transport_query.iter() {
         let start = planet_query
             .get(transit.route[0])
             .unwrap()
@@ -235,6 +394,17 @@ fn render_transports(
         let pos = start + (end - start) * transit.position;
 
         painter.set_translation(pos);
-        painter.ngon(3.0, RESOURCE_RADIUS);
+        painter.set_color(cargo_color(&palette, *resource, age));
+        painter.ngon(resource.sides(), RESOURCE_RADIUS);
+
+        // Cargo being refined shows how far along it is.
+        if let Some(processing) = transit.processing {
+            painter.hollow = true;
+            painter.thickness = 1.0;
+            painter.arc(RESOURCE_RADIUS + 3.0, 0.0, processing.progress() * 2.0 * PI);
+            painter.hollow = false;
+        }
     }
+
+    painter.set_translation(Vec3::ZERO);
 }
@@ -0,0 +1,286 @@
+//! Research bought with income during a run. Every node unlocks an upgrade, and what is
+//! unlocked is summed up in [`ResearchModifiers`], which the rest of the game applies
+//! on top of its base values.
+
+use bevy::{prelude::*, utils::HashSet};
+
+use super::{
+    ledger::Ledger,
+    resource::ResourceContainer,
+    spawn::connection::{ConnectionConfig, ConnectionUnderConstruction, LinkKind},
+};
+use crate::{
+    screen::Screen,
+    settings::{BindingAction, Settings},
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Research>();
+    app.init_resource::<ResearchModifiers>();
+    app.init_resource::<SelectedLink>();
+
+    app.add_systems(
+        OnEnter(Screen::Playing),
+        (reset_research, reset_selected_link),
+    );
+    app.observe(unlock_research);
+    app.observe(cycle_link_kind);
+    app.add_systems(
+        Update,
+        cycle_link_kind_key
+            .in_set(AppSet::RecordInput)
+            .run_if(in_state(Screen::Playing)),
+    );
+    app.add_systems(
+        Update,
+        (
+            update_research_modifiers.run_if(resource_changed::<Research>),
+            apply_storage_research,
+        )
+            .chain()
+            .in_set(AppSet::PrepareUpdate)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// An upgrade in the research tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum ResearchNode {
+    ExtendedRange,
+    DeepSpaceRange,
+    LongHaulLinks,
+    ImprovedEngines,
+    FusionEngines,
+    ExpressLinks,
+    ExpandedHolds,
+    CargoCompression,
+    RelayStations,
+}
+
+impl ResearchNode {
+    /// The branches of the tree, each listed from its root.
+    pub const BRANCHES: [(&'static str, &'static [Self]); 4] = [
+        (
+            "Range",
+            &[
+                Self::ExtendedRange,
+                Self::DeepSpaceRange,
+                Self::LongHaulLinks,
+            ],
+        ),
+        (
+            "Engines",
+            &[
+                Self::ImprovedEngines,
+                Self::FusionEngines,
+                Self::ExpressLinks,
+            ],
+        ),
+        ("Storage", &[Self::ExpandedHolds, Self::CargoCompression]),
+        ("Network", &[Self::RelayStations]),
+    ];
+
+    pub fn cost(&self) -> i32 {
+        match self {
+            Self::ExtendedRange | Self::ImprovedEngines => 400,
+            Self::DeepSpaceRange | Self::FusionEngines => 900,
+            Self::LongHaulLinks | Self::ExpressLinks => 600,
+            Self::ExpandedHolds => 300,
+            Self::CargoCompression => 700,
+            Self::RelayStations => 300,
+        }
+    }
+
+    /// What has to be researched before this node.
+    pub fn prerequisite(&self) -> Option<Self> {
+        match self {
+            Self::DeepSpaceRange | Self::LongHaulLinks => Some(Self::ExtendedRange),
+            Self::FusionEngines | Self::ExpressLinks => Some(Self::ImprovedEngines),
+            Self::CargoCompression => Some(Self::ExpandedHolds),
+            Self::ExtendedRange
+            | Self::ImprovedEngines
+            | Self::ExpandedHolds
+            | Self::RelayStations => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::ExtendedRange => "Extended Range",
+            Self::DeepSpaceRange => "Deep Space Range",
+            Self::LongHaulLinks => "Long-haul Links",
+            Self::ImprovedEngines => "Improved Engines",
+            Self::FusionEngines => "Fusion Engines",
+            Self::ExpressLinks => "Express Links",
+            Self::ExpandedHolds => "Expanded Holds",
+            Self::CargoCompression => "Cargo Compression",
+            Self::RelayStations => "Relay Stations",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::ExtendedRange => "Connections reach 25% further.",
+            Self::DeepSpaceRange => "Connections reach another 25% further.",
+            Self::LongHaulLinks => "Unlocks long-haul links, which reach further but are slower.",
+            Self::ImprovedEngines => "Ships fly 25% faster.",
+            Self::FusionEngines => "Ships fly another 25% faster.",
+            Self::ExpressLinks => "Unlocks express links, which are faster but shorter.",
+            Self::ExpandedHolds => "Every satellite stores 2 more cargo.",
+            Self::CargoCompression => "Every satellite stores another 2 cargo.",
+            Self::RelayStations => "Relay stations can be built in space and on Lagrange points.",
+        }
+    }
+
+    fn apply(&self, modifiers: &mut ResearchModifiers) {
+        match self {
+            Self::ExtendedRange | Self::DeepSpaceRange => modifiers.range += 0.25,
+            Self::ImprovedEngines | Self::FusionEngines => modifiers.transport_speed += 0.25,
+            Self::ExpandedHolds | Self::CargoCompression => modifiers.extra_storage += 2,
+            Self::LongHaulLinks => modifiers.link_kinds.push(LinkKind::LongHaul),
+            Self::ExpressLinks => modifiers.link_kinds.push(LinkKind::Express),
+            Self::RelayStations => modifiers.relay_stations = true,
+        }
+    }
+}
+
+/// The research done in this run.
+#[derive(Resource, Debug, Default)]
+pub struct Research {
+    unlocked: HashSet<ResearchNode>,
+}
+
+impl Research {
+    pub fn is_unlocked(&self, node: ResearchNode) -> bool {
+        self.unlocked.contains(&node)
+    }
+
+    /// Whether `node` is still to be researched and everything it needs has been.
+    pub fn is_available(&self, node: ResearchNode) -> bool {
+        !self.is_unlocked(node)
+            && node
+                .prerequisite()
+                .into_iter()
+                .all(|prerequisite| self.is_unlocked(prerequisite))
+    }
+}
+
+/// Research `node` if it is available and can be paid for.
+#[derive(Event, Debug)]
+pub struct ResearchRequested(pub ResearchNode);
+
+/// Everything unlocked by research, applied on top of the base values.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct ResearchModifiers {
+    /// Multiplies [`ConnectionConfig::range`].
+    pub range: f32,
+    /// Multiplies how fast ships fly.
+    pub transport_speed: f32,
+    /// Added to the storage of every satellite.
+    pub extra_storage: usize,
+    /// The kinds of link the player can build.
+    pub link_kinds: Vec<LinkKind>,
+    pub relay_stations: bool,
+}
+
+impl Default for ResearchModifiers {
+    fn default() -> Self {
+        Self {
+            range: 1.0,
+            transport_speed: 1.0,
+            extra_storage: 0,
+            link_kinds: vec![LinkKind::Standard],
+            relay_stations: false,
+        }
+    }
+}
+
+impl ResearchModifiers {
+    /// How far a link of this kind reaches, before alignments.
+    pub fn connection_range(&self, config: &ConnectionConfig, kind: LinkKind) -> f32 {
+        config.range * self.range * kind.range_factor()
+    }
+}
+
+/// Switch to the next kind of link that has been researched.
+#[derive(Event, Debug)]
+pub struct CycleLinkKind;
+
+/// The kind of link new connections are built as.
+#[derive(Resource, Debug, Default)]
+pub struct SelectedLink(pub LinkKind);
+
+fn reset_research(mut research: ResMut<Research>) {
+    *research = Research::default();
+}
+
+fn reset_selected_link(mut selected: ResMut<SelectedLink>) {
+    *selected = SelectedLink::default();
+}
+
+fn unlock_research(
+    trigger: Trigger<ResearchRequested>,
+    mut research: ResMut<Research>,
+    mut ledger: ResMut<Ledger>,
+) {
+    let node = trigger.event().0;
+    if !research.is_available(node) || ledger.money < node.cost() {
+        return;
+    }
+
+    ledger.money -= node.cost();
+    research.unlocked.insert(node);
+}
+
+fn update_research_modifiers(research: Res<Research>, mut modifiers: ResMut<ResearchModifiers>) {
+    let mut updated = ResearchModifiers::default();
+    for node in &research.unlocked {
+        node.apply(&mut updated);
+    }
+    if *modifiers != updated {
+        *modifiers = updated;
+    }
+}
+
+fn apply_storage_research(
+    modifiers: Res<ResearchModifiers>,
+    mut container_query: Query<&mut ResourceContainer>,
+) {
+    for mut container in &mut container_query {
+        if container.extra_storage != modifiers.extra_storage {
+            container.extra_storage = modifiers.extra_storage;
+        }
+    }
+}
+
+fn cycle_link_kind_key(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+) {
+    if input.just_pressed(settings.key_bindings.get(BindingAction::CycleLink)) {
+        commands.trigger(CycleLinkKind);
+    }
+}
+
+/// A connection that is being built switches along with the selection.
+fn cycle_link_kind(
+    _trigger: Trigger<CycleLinkKind>,
+    modifiers: Res<ResearchModifiers>,
+    mut selected: ResMut<SelectedLink>,
+    mut construction_query: Query<&mut LinkKind, With<ConnectionUnderConstruction>>,
+) {
+    let kinds: Vec<_> = LinkKind::ALL
+        .into_iter()
+        .filter(|kind| modifiers.link_kinds.contains(kind))
+        .collect();
+    let index = kinds.iter().position(|kind| *kind == selected.0);
+    let next = kinds[index.map_or(0, |index| (index + 1) % kinds.len())];
+    if selected.0 != next {
+        selected.0 = next;
+        for mut kind in &mut construction_query {
+            *kind = next;
+        }
+    }
+}
@@ -1,13 +1,24 @@
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::Duration,
+};
 
 use bevy::prelude::*;
-use rand::Rng;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
 
 use crate::{screen::Screen, AppSet};
 
-use super::spawn::{
-    connection::{ConnectionAnchor, ConnectionTarget, ConnectionUnderConstruction},
-    planet::OrbitalPosition,
+use super::{
+    assets::SfxKey,
+    audio::sfx::PlaySfx,
+    ledger::{CargoLost, OrderFailed, OrderFulfilled},
+    research::ResearchModifiers,
+    rng::GameRng,
+    spawn::{
+        connection::{ConnectionAnchor, ConnectionTarget, ConnectionUnderConstruction, LinkKind},
+        planet::OrbitalPosition,
+    },
 };
 
 pub(super) fn plugin(app: &mut App) {
@@ -16,20 +27,28 @@ pub(super) fn plugin(app: &mut App) {
     });
 
     app.insert_resource(ResourceDemandTimer {
-        timer: Timer::from_seconds(5.0, TimerMode::Repeating),
+        timer: Timer::from_seconds(DEMAND_INTERVAL_SECS, TimerMode::Repeating),
     });
 
+    app.init_resource::<DemandRules>();
+    app.init_resource::<OverflowPolicy>();
+    app.add_systems(OnEnter(Screen::Playing), reset_demand_rules);
+
     app.insert_resource(ResourceTransportTimer {
         timer: Timer::from_seconds(0.5, TimerMode::Repeating),
     });
 
     app.add_systems(Update, tick_resource_timers.in_set(AppSet::TickTimers));
     app.add_systems(Update, tick_transport_timers.in_set(AppSet::TickTimers));
+    app.add_systems(Update, tick_demand_deadlines.in_set(AppSet::TickTimers));
+    app.add_systems(Update, age_cargo.in_set(AppSet::TickTimers));
     app.add_systems(
         Update,
         (
+            store_waiting_cargo,
             process_unclaimed_resources,
             (update_transport, process_transit_stops).chain(),
+            process_cargo_en_route,
         )
             .in_set(AppSet::Update),
     );
@@ -50,6 +69,28 @@ pub struct ResourceDemandTimer {
     pub timer: Timer,
 }
 
+/// Seconds between waves of orders, unless the level changes it.
+pub const DEMAND_INTERVAL_SECS: f32 = 5.0;
+
+/// What a wave of orders can contain. Campaign levels keep the defaults,
+/// endless mode's director changes them as the run goes on.
+#[derive(Resource, Debug, Clone)]
+pub struct DemandRules {
+    /// The most orders a single consumer can place in one wave.
+    pub max_per_wave: u32,
+    /// The kinds of cargo that can be ordered.
+    pub resources: Vec<GameResource>,
+}
+
+impl Default for DemandRules {
+    fn default() -> Self {
+        Self {
+            max_per_wave: 3,
+            resources: vec![GameResource::Material],
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct ResourceTransportTimer {
     pub timer: Timer,
@@ -69,14 +110,121 @@ pub struct ResourceSpawner {
     pub spawn_type: GameResource,
 }
 
+/// Stops a spawner from producing cargo, for as long as it has this.
+#[derive(Component, Debug)]
+pub struct ProductionHalted;
+
+/// The most a warehouse can store.
+pub const WAREHOUSE_STORAGE_SIZE: usize = 24;
+
+/// A satellite with lots of room, used to buffer cargo that has nowhere else to go.
+/// Full spawners send their cargo to the nearest warehouse with room instead of stopping.
+#[derive(Component, Debug)]
+pub struct Warehouse;
+
+/// What happens to cargo that stops at a satellite with no room left in its storage.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OverflowPolicy {
+    /// The satellite turns the cargo away and it waits there until there is room.
+    #[default]
+    Reject,
+    /// The cargo is thrown away, which costs money.
+    Destroy,
+    /// The cargo moves on to the nearest connected satellite with room, warehouses first.
+    /// Full spawners keep producing and spill their cargo too.
+    Spill,
+}
+
+/// Cargo that was turned away by a full satellite, waiting for room in its storage.
+#[derive(Component, Debug)]
+pub struct WaitingForRoom {
+    pub satellite: Entity,
+}
+
 #[derive(Component)]
 pub struct ResourceConsumer {
     pub demands: Vec<GameResource>,
 }
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum GameResource {
     Material,
+    Fuel,
+    Crystal,
+    /// Spoils if it isn't delivered quickly.
+    Food,
+}
+
+impl GameResource {
+    pub const ALL: [Self; 4] = [Self::Material, Self::Fuel, Self::Crystal, Self::Food];
+
+    /// Cargo is drawn as a polygon with this many sides, so every kind has its own shape.
+    pub fn sides(&self) -> f32 {
+        match self {
+            GameResource::Material => 3.0,
+            GameResource::Fuel => 4.0,
+            GameResource::Crystal => 6.0,
+            GameResource::Food => 5.0,
+        }
+    }
+
+    /// Seconds the cargo keeps, in storage or in transit, if it spoils at all.
+    pub fn shelf_life(&self) -> Option<f32> {
+        match self {
+            GameResource::Food => Some(FOOD_SHELF_LIFE_SECS),
+            _ => None,
+        }
+    }
+}
+
+/// How long food keeps.
+const FOOD_SHELF_LIFE_SECS: f32 = 40.0;
+
+/// How long cargo is expected to wait for a ship at each stop, when checking it will arrive fresh.
+const STOP_WAIT_SECS: f32 = 0.5;
+
+/// How long a piece of cargo has existed, so perishable cargo can spoil.
+#[derive(Component, Debug, Default)]
+pub struct Age(pub f32);
+
+impl Age {
+    /// How much of its shelf life `resource` has left, from 1 when fresh down to 0.
+    pub fn freshness(&self, resource: GameResource) -> f32 {
+        resource
+            .shelf_life()
+            .map_or(1.0, |life| (1.0 - self.0 / life).clamp(0.0, 1.0))
+    }
+}
+
+/// Turns one kind of cargo into another while it travels.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Recipe {
+    pub input: GameResource,
+    pub output: GameResource,
+    /// Seconds of travel the cargo needs, counted from when it leaves the refinery.
+    pub duration: f32,
+}
+
+/// A satellite whose ships refine cargo on the way out, see [`Recipe`].
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Refinery(pub Recipe);
+
+/// Cargo being refined on board a ship.
+#[derive(Debug, Clone, Copy)]
+pub struct Processing {
+    pub recipe: Recipe,
+    /// The satellite where refining starts.
+    pub refinery: Entity,
+    /// Whether the cargo has left the refinery yet.
+    pub departed: bool,
+    /// Seconds of travel since leaving the refinery.
+    pub elapsed: f32,
+}
+
+impl Processing {
+    pub fn progress(&self) -> f32 {
+        (self.elapsed / self.recipe.duration.max(f32::EPSILON)).min(1.0)
+    }
 }
 
 #[derive(Component)]
@@ -87,23 +235,92 @@ pub struct GameResourceInStorage {
 #[derive(Component)]
 pub struct GameResourceInTransit {
     pub route: Vec<Entity>,
-    pub claim: Entity,
+    /// The order the cargo was sent for. Spilled cargo isn't going to an order.
+    pub claim: Option<Entity>,
     pub position: f32,
+    /// Set when the cargo is claimed as another kind and turns into it along the way.
+    pub processing: Option<Processing>,
 }
 
 #[derive(Component)]
 struct UpdateProgress;
 
+/// Triggered when cargo finishes travelling along a single connection.
+#[derive(Event, Debug)]
+pub struct CargoHopped {
+    pub from: Entity,
+    pub to: Entity,
+}
+
+/// How long a demand may wait for cargo to be dispatched before it fails.
+pub const DEMAND_DEADLINE_SECS: f32 = 45.0;
+
+/// How urgent an order is. Urgent orders pay more but fail sooner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum OrderPriority {
+    #[default]
+    Normal,
+    Express,
+    Critical,
+}
+
+impl OrderPriority {
+    /// Roll the priority of a new order.
+    fn roll(rng: &mut impl Rng) -> Self {
+        match rng.gen_range(0.0..1.0) {
+            roll if roll < 0.05 => OrderPriority::Critical,
+            roll if roll < 0.2 => OrderPriority::Express,
+            _ => OrderPriority::Normal,
+        }
+    }
+
+    pub fn deadline_secs(&self) -> f32 {
+        match self {
+            OrderPriority::Normal => DEMAND_DEADLINE_SECS,
+            OrderPriority::Express => 30.0,
+            OrderPriority::Critical => 20.0,
+        }
+    }
+
+    /// What an order of this priority pays, for cargo worth `price` at the consumer's market.
+    pub fn payout(&self, price: i32) -> i32 {
+        match self {
+            OrderPriority::Normal => price,
+            OrderPriority::Express => price * 3 / 2,
+            OrderPriority::Critical => price * 5 / 2,
+        }
+    }
+}
+
+/// A consumer the player wants served first. Its orders get cargo before anyone else's.
+#[derive(Component, Debug)]
+pub struct PinnedConsumer;
+
 #[derive(Component)]
 pub struct GameResourceDemand {
     pub satellite: Entity,
+    pub priority: OrderPriority,
     pub claim: Option<Entity>,
+    /// Only ticks while the demand is unclaimed.
+    pub deadline: Timer,
 }
 
 #[derive(Component)]
 pub struct ResourceContainer {
     pub storage_count: usize,
     pub storage_size: usize,
+    /// Room added by research on top of `storage_size`.
+    pub extra_storage: usize,
+}
+
+impl ResourceContainer {
+    pub fn capacity(&self) -> usize {
+        self.storage_size + self.extra_storage
+    }
+
+    pub fn has_room(&self) -> bool {
+        self.storage_count < self.capacity()
+    }
 }
 
 #[derive(Component)]
@@ -113,6 +330,7 @@ pub struct PendingDeparture;
 struct GameResourceBundle {
     resource: GameResource,
     storage: GameResourceInStorage,
+    age: Age,
 }
 
 #[derive(Bundle)]
@@ -121,6 +339,17 @@ struct GameResourceDemandBundle {
     demand: GameResourceDemand,
 }
 
+fn reset_demand_rules(
+    mut rules: ResMut<DemandRules>,
+    mut demand_time: ResMut<ResourceDemandTimer>,
+) {
+    *rules = DemandRules::default();
+    demand_time
+        .timer
+        .set_duration(Duration::from_secs_f32(DEMAND_INTERVAL_SECS));
+    demand_time.timer.reset();
+}
+
 fn tick_resource_timers(
     mut commands: Commands,
     time: Res<Time>,
@@ -151,19 +380,83 @@ fn tick_transport_timers(
     }
 }
 
+fn tick_demand_deadlines(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut demand_query: Query<(Entity, &mut GameResourceDemand)>,
+) {
+    for (entity, mut demand) in &mut demand_query {
+        if demand.claim.is_some() {
+            continue;
+        }
+
+        demand.deadline.tick(time.delta());
+        if demand.deadline.just_finished() {
+            commands.trigger(OrderFailed {
+                satellite: demand.satellite,
+            });
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Perishable cargo that gets too old spoils and is thrown away.
+/// Cargo in transit is lost along with its claim, cargo in storage frees up its slot.
+fn age_cargo(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut cargo_query: Query<(
+        Entity,
+        &GameResource,
+        &mut Age,
+        Option<&GameResourceInStorage>,
+        Option<&GameResourceInTransit>,
+    )>,
+    mut demand_query: Query<&mut GameResourceDemand>,
+    mut container_query: Query<&mut ResourceContainer>,
+) {
+    for (entity, resource, mut age, storage, transit) in &mut cargo_query {
+        age.0 += time.delta_seconds();
+        let Some(shelf_life) = resource.shelf_life() else {
+            continue;
+        };
+        if age.0 < shelf_life {
+            continue;
+        }
+
+        if let Some(transit) = transit {
+            if let Some(Ok(mut demand)) = transit.claim.map(|claim| demand_query.get_mut(claim)) {
+                demand.claim = None;
+            }
+        } else if let Some(Ok(mut container)) =
+            storage.map(|s| container_query.get_mut(s.satellite))
+        {
+            container.storage_count = container.storage_count.saturating_sub(1);
+        }
+        commands.entity(entity).despawn();
+    }
+}
+
 fn process_demands(
     mut commands: Commands,
+    mut game_rng: ResMut<GameRng>,
     mut consumer_query: Query<(Entity, &mut ResourceConsumer), Changed<ResourceConsumer>>,
 ) {
     for (satellite, mut consumer) in consumer_query.iter_mut() {
         if !consumer.demands.is_empty() {
             for demand in consumer.demands.iter() {
+                let priority = OrderPriority::roll(&mut game_rng.rng);
                 commands.spawn((
                     GameResourceDemandBundle {
                         resource: *demand,
                         demand: GameResourceDemand {
                             satellite,
+                            priority,
                             claim: None,
+                            deadline: Timer::from_seconds(
+                                priority.deadline_secs(),
+                                TimerMode::Once,
+                            ),
                         },
                     },
                     StateScoped(Screen::Playing),
@@ -178,29 +471,71 @@ fn process_demands(
 fn process_spawn_resource(
     _trigger: Trigger<DoResourceSpawn>,
     mut commands: Commands,
-    mut spawner_query: Query<(Entity, &ResourceSpawner, &mut ResourceContainer)>,
+    policy: Res<OverflowPolicy>,
+    spawner_query: Query<(Entity, &ResourceSpawner), Without<ProductionHalted>>,
+    mut container_query: Query<&mut ResourceContainer>,
+    connection_query: Query<
+        (&ConnectionAnchor, &ConnectionTarget),
+        Without<ConnectionUnderConstruction>,
+    >,
+    warehouse_query: Query<(), With<Warehouse>>,
 ) {
-    for (entity, spawner, mut container) in &mut spawner_query {
-        if container.storage_count < container.storage_size {
+    for (entity, spawner) in &spawner_query {
+        let Ok(mut container) = container_query.get_mut(entity) else {
+            continue;
+        };
+        if container.has_room() {
             commands.spawn((
                 GameResourceBundle {
                     resource: spawner.spawn_type,
                     storage: GameResourceInStorage { satellite: entity },
+                    age: Age::default(),
                 },
                 StateScoped(Screen::Playing),
             ));
             container.storage_count += 1;
+            continue;
+        }
+
+        // A full spawner keeps producing while its cargo has somewhere else to go.
+        let warehouses_only = *policy != OverflowPolicy::Spill;
+        if let Some(route) = spill_route(
+            entity,
+            warehouses_only,
+            &connection_query,
+            &container_query,
+            &warehouse_query,
+        ) {
+            commands.spawn((
+                spawner.spawn_type,
+                GameResourceInTransit {
+                    route,
+                    claim: None,
+                    position: 0.0,
+                    processing: None,
+                },
+                Age::default(),
+                UpdateProgress,
+                StateScoped(Screen::Playing),
+            ));
         }
     }
 }
 
 fn process_demand_resources(
     _trigger: Trigger<DoResourceDemand>,
+    rules: Res<DemandRules>,
+    mut game_rng: ResMut<GameRng>,
     mut consumer_query: Query<&mut ResourceConsumer>,
 ) {
     for mut consumer in consumer_query.iter_mut() {
-        for _ in 0..rand::thread_rng().gen_range(0..=3) {
-            consumer.demands.push(GameResource::Material);
+        for _ in 0..game_rng.rng.gen_range(0..=rules.max_per_wave) {
+            let resource = rules
+                .resources
+                .choose(&mut game_rng.rng)
+                .copied()
+                .unwrap_or(GameResource::Material);
+            consumer.demands.push(resource);
         }
     }
 }
@@ -208,15 +543,26 @@ fn process_demand_resources(
 fn process_resource_departures(
     _trigger: Trigger<DoResourceDepartures>,
     mut commands: Commands,
-    awaiting_transport_query: Query<(Entity, &GameResourceInTransit), With<PendingDeparture>>,
-    planet_query: Query<Entity, With<OrbitalPosition>>,
+    mut awaiting_transport_query: Query<
+        (Entity, &mut GameResourceInTransit),
+        With<PendingDeparture>,
+    >,
+    planet_query: Query<(Entity, &OrbitalPosition)>,
 ) {
-    'outer: for satellite in planet_query.iter() {
-        for (resource_entity, transit) in awaiting_transport_query.iter() {
-            if transit.route[0] == satellite {
+    'outer: for (satellite, orbital_position) in planet_query.iter() {
+        for (resource_entity, mut transit) in awaiting_transport_query.iter_mut() {
+            // Cargo at its destination is only waiting to be refined.
+            if transit.route[0] == satellite && transit.route.len() > 1 {
+                if let Some(processing) = &mut transit.processing {
+                    processing.departed |= processing.refinery == satellite;
+                }
                 commands
                     .entity(resource_entity)
                     .remove::<PendingDeparture>();
+                commands.trigger(PlaySfx::At(
+                    SfxKey::Departure,
+                    orbital_position.get_euclidean_position(),
+                ));
                 break 'outer;
             }
         }
@@ -225,77 +571,147 @@ fn process_resource_departures(
 
 fn process_unclaimed_resources(
     mut commands: Commands,
-    resource_in_storage_query: Query<(Entity, &GameResource, &GameResourceInStorage)>,
+    resource_in_storage_query: Query<(Entity, &GameResource, &GameResourceInStorage, &Age)>,
     mut demand_query: Query<(&mut GameResourceDemand, &GameResource, Entity)>,
     mut container_query: Query<&mut ResourceContainer>,
     connection_query: Query<
-        (&ConnectionAnchor, &ConnectionTarget, Entity),
+        (&ConnectionAnchor, &ConnectionTarget, &LinkKind),
         Without<ConnectionUnderConstruction>,
     >,
+    modifiers: Res<ResearchModifiers>,
+    refinery_query: Query<&Refinery>,
+    planet_query: Query<&OrbitalPosition>,
+    pinned_query: Query<(), With<PinnedConsumer>>,
 ) {
     let mut claimed_this_frame = HashSet::new();
 
-    for (mut demand, demanded_resource, demand_entity) in &mut demand_query {
-        if demand.claim.is_none() {
-            // Search for a resource in storage that is unclaimed
-            let mut visited = HashSet::new();
-            let mut open_list: Vec<(Entity, Vec<Entity>)> = Vec::new();
-            open_list.push((demand.satellite, vec![demand.satellite]));
-
-            'search_for_claim: while !open_list.is_empty() {
-                let (cur_planet, mut planet_path) = open_list.remove(0);
-                visited.insert(cur_planet);
-
-                // Check for any resources
-                for (resource_entity, resource, storage) in &resource_in_storage_query {
-                    if !claimed_this_frame.contains(&resource_entity)
-                        && resource == demanded_resource
+    // When stock is scarce, pinned consumers are served first, then the most urgent orders,
+    // then the ones closest to failing.
+    let mut unclaimed_demands: Vec<_> = demand_query
+        .iter()
+        .filter(|(demand, ..)| demand.claim.is_none())
+        .map(|(demand, _, entity)| {
+            (
+                pinned_query.contains(demand.satellite),
+                demand.priority,
+                demand.deadline.remaining_secs(),
+                entity,
+            )
+        })
+        .collect();
+    unclaimed_demands.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)).then(a.2.total_cmp(&b.2)));
+
+    for (.., demand_entity) in unclaimed_demands {
+        let Ok((mut demand, demanded_resource, _)) = demand_query.get_mut(demand_entity) else {
+            continue;
+        };
+        // Perishable cargo takes the quickest route rather than the one with the fewest stops,
+        // and only cargo that will still be fresh when it arrives can be claimed.
+        let shelf_life = demanded_resource.shelf_life();
+
+        // Search for a resource in storage that is unclaimed
+        let mut visited = HashSet::new();
+        let mut open_list: Vec<(Entity, Vec<Entity>, f32)> = Vec::new();
+        open_list.push((demand.satellite, vec![demand.satellite], 0.0));
+
+        'search_for_claim: while !open_list.is_empty() {
+            let next = match shelf_life {
+                Some(_) => open_list
+                    .iter()
+                    .enumerate()
+                    .min_by(|(_, a), (_, b)| a.2.total_cmp(&b.2))
+                    .map_or(0, |(index, _)| index),
+                None => 0,
+            };
+            let (cur_planet, mut planet_path, flight_secs) = open_list.remove(next);
+            if !visited.insert(cur_planet) {
+                continue;
+            }
+            let travel_secs = flight_secs + planet_path.len() as f32 * STOP_WAIT_SECS;
+
+            // Check for any resources. Cargo of another kind will do if a refinery on the way
+            // turns it into the right one, but cargo that is already right comes first.
+            // The destination doesn't count as a refinery, cargo has to leave one to be refined.
+            let unclaimed: Vec<_> = resource_in_storage_query
+                .iter()
+                .filter(|(entity, _, storage, age)| {
+                    !claimed_this_frame.contains(entity)
                         && storage.satellite == cur_planet
-                    {
-                        demand.claim = Some(resource_entity);
-
-                        planet_path.reverse();
-                        commands
-                            .entity(resource_entity)
-                            .remove::<GameResourceInStorage>()
-                            .insert((
-                                GameResourceInTransit {
-                                    route: planet_path,
-                                    claim: demand_entity,
-                                    position: 0.0,
-                                },
-                                UpdateProgress,
-                            ));
-
-                        if let Ok(mut container) = container_query.get_mut(storage.satellite) {
-                            if container.storage_count > 0 {
-                                container.storage_count -= 1;
-                            } else {
-                                error!("Storage was empty when resource was removed!")
-                            }
+                        && match shelf_life {
+                            Some(shelf_life) => age.0 + travel_secs < shelf_life,
+                            None => true,
                         }
+                })
+                .map(|(entity, resource, storage, _)| (entity, resource, storage))
+                .collect();
+            let claim = unclaimed
+                .iter()
+                .find(|(_, resource, _)| *resource == demanded_resource)
+                .map(|&(entity, _, storage)| (entity, storage, None))
+                .or_else(|| {
+                    unclaimed.iter().find_map(|&(entity, resource, storage)| {
+                        planet_path[1..].iter().rev().find_map(|satellite| {
+                            let recipe = refinery_query.get(*satellite).ok()?.0;
+                            (recipe.input == *resource && recipe.output == *demanded_resource)
+                                .then_some((
+                                    entity,
+                                    storage,
+                                    Some(Processing {
+                                        recipe,
+                                        refinery: *satellite,
+                                        departed: false,
+                                        elapsed: 0.0,
+                                    }),
+                                ))
+                        })
+                    })
+                });
+
+            if let Some((resource_entity, storage, processing)) = claim {
+                demand.claim = Some(resource_entity);
+
+                planet_path.reverse();
+                commands
+                    .entity(resource_entity)
+                    .remove::<GameResourceInStorage>()
+                    .insert((
+                        GameResourceInTransit {
+                            route: planet_path,
+                            claim: Some(demand_entity),
+                            position: 0.0,
+                            processing,
+                        },
+                        UpdateProgress,
+                    ));
 
-                        claimed_this_frame.insert(resource_entity);
-                        break 'search_for_claim;
+                if let Ok(mut container) = container_query.get_mut(storage.satellite) {
+                    if container.storage_count > 0 {
+                        container.storage_count -= 1;
+                    } else {
+                        error!("Storage was empty when resource was removed!")
                     }
                 }
 
-                // Otherwise, keep searching
-                for (anchor, target, _) in &connection_query {
-                    if let ConnectionTarget::Satellite(target_entity) = target {
-                        if anchor.satellite == cur_planet {
-                            if !visited.contains(target_entity) {
-                                let mut new_path = planet_path.clone();
-                                new_path.push(*target_entity);
-                                open_list.push((*target_entity, new_path));
-                            }
-                        } else if *target_entity == cur_planet
-                            && !visited.contains(&anchor.satellite)
-                        {
+                claimed_this_frame.insert(resource_entity);
+                break 'search_for_claim;
+            }
+
+            // Otherwise, keep searching
+            for (anchor, target, kind) in &connection_query {
+                if let ConnectionTarget::Satellite(target_entity) = target {
+                    let speed = TRANSPORT_SPEED * modifiers.transport_speed * kind.speed_factor();
+                    if anchor.satellite == cur_planet {
+                        if !visited.contains(target_entity) {
                             let mut new_path = planet_path.clone();
-                            new_path.push(anchor.satellite);
-                            open_list.push((anchor.satellite, new_path));
+                            new_path.push(*target_entity);
+                            let hop = hop_length(&planet_query, cur_planet, *target_entity);
+                            open_list.push((*target_entity, new_path, flight_secs + hop / speed));
                         }
+                    } else if *target_entity == cur_planet && !visited.contains(&anchor.satellite) {
+                        let mut new_path = planet_path.clone();
+                        new_path.push(anchor.satellite);
+                        let hop = hop_length(&planet_query, cur_planet, anchor.satellite);
+                        open_list.push((anchor.satellite, new_path, flight_secs + hop / speed));
                     }
                 }
             }
@@ -305,12 +721,71 @@ fn process_unclaimed_resources(
 
 const TRANSPORT_SPEED: f32 = 240.0;
 
+/// How far apart two satellites are right now.
+fn hop_length(planet_query: &Query<&OrbitalPosition>, from: Entity, to: Entity) -> f32 {
+    match (planet_query.get(from), planet_query.get(to)) {
+        (Ok(from), Ok(to)) => from
+            .get_euclidean_position()
+            .distance(to.get_euclidean_position()),
+        _ => 0.0,
+    }
+}
+
+/// Cargo is refined once it has left the refinery, including while it waits for a ship at later stops.
+fn process_cargo_en_route(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut transit_query: Query<(
+        Entity,
+        &mut GameResource,
+        &mut Age,
+        &mut GameResourceInTransit,
+    )>,
+) {
+    for (entity, mut resource, mut age, mut transit) in &mut transit_query {
+        let Some(mut processing) = transit.processing else {
+            continue;
+        };
+        if !processing.departed {
+            continue;
+        }
+
+        processing.elapsed += time.delta_seconds();
+        if processing.elapsed >= processing.recipe.duration {
+            // Refined cargo is fresh, whatever state its input was in.
+            *resource = processing.recipe.output;
+            age.0 = 0.0;
+            transit.processing = None;
+            if transit.route.len() < 2 {
+                // Finished at the destination, so the order can be fulfilled.
+                commands
+                    .entity(entity)
+                    .remove::<PendingDeparture>()
+                    .insert(UpdateProgress);
+            }
+        } else {
+            transit.processing = Some(processing);
+        }
+    }
+}
+
+/// Ships fly as fast as research allows, scaled by the kind of link they fly along.
 fn update_transport(
     mut commands: Commands,
     time: Res<Time>,
+    modifiers: Res<ResearchModifiers>,
     mut transporting_query: Query<(Entity, &mut GameResourceInTransit), Without<PendingDeparture>>,
+    connection_query: Query<(&ConnectionAnchor, &ConnectionTarget, &LinkKind)>,
     planet_query: Query<&OrbitalPosition>,
 ) {
+    let mut link_speeds = HashMap::new();
+    for (anchor, target, kind) in &connection_query {
+        if let ConnectionTarget::Satellite(target) = *target {
+            link_speeds.insert((anchor.satellite, target), kind.speed_factor());
+            link_speeds.insert((target, anchor.satellite), kind.speed_factor());
+        }
+    }
+
     for (resource_entity, mut transit) in transporting_query.iter_mut() {
         let start = planet_query
             .get(transit.route[0])
@@ -323,11 +798,20 @@ fn update_transport(
         let distance = start.distance(end);
 
         let current = distance * transit.position;
-        let next = current + time.delta().as_secs_f32() * TRANSPORT_SPEED;
+        let link_speed = link_speeds
+            .get(&(transit.route[0], transit.route[1]))
+            .copied()
+            .unwrap_or(1.0);
+        let speed = TRANSPORT_SPEED * modifiers.transport_speed * link_speed;
+        let next = current + time.delta().as_secs_f32() * speed;
 
         transit.position = next / distance;
 
         if transit.position >= 1.0 {
+            commands.trigger(CargoHopped {
+                from: transit.route[0],
+                to: transit.route[1],
+            });
             transit.route.remove(0);
             commands.entity(resource_entity).insert(UpdateProgress);
         }
@@ -336,20 +820,62 @@ fn update_transport(
 
 fn process_transit_stops(
     mut commands: Commands,
-    mut transporting_query: Query<(Entity, &mut GameResourceInTransit), With<UpdateProgress>>,
+    policy: Res<OverflowPolicy>,
+    mut transporting_query: Query<
+        (Entity, &GameResource, &mut GameResourceInTransit),
+        With<UpdateProgress>,
+    >,
     mut demand_query: Query<&mut GameResourceDemand>,
     mut container_query: Query<&mut ResourceContainer>,
     connection_query: Query<
         (&ConnectionAnchor, &ConnectionTarget),
         Without<ConnectionUnderConstruction>,
     >,
+    planet_query: Query<&OrbitalPosition>,
+    warehouse_query: Query<(), With<Warehouse>>,
 ) {
-    for (entity, mut transit) in transporting_query.iter_mut() {
+    for (entity, resource, mut transit) in transporting_query.iter_mut() {
         // transit.route.remove(0);
 
         if transit.route.len() < 2 {
+            let Some(claim) = transit.claim else {
+                // Spilled cargo only needs somewhere to be stored.
+                store_cargo(
+                    &mut commands,
+                    *policy,
+                    entity,
+                    transit.route[0],
+                    &mut container_query,
+                    &connection_query,
+                    &warehouse_query,
+                );
+                continue;
+            };
+
+            // Cargo that is still being refined waits at its destination until it is done.
+            if transit.processing.is_some() && demand_query.contains(claim) {
+                commands
+                    .entity(entity)
+                    .remove::<UpdateProgress>()
+                    .insert(PendingDeparture);
+                continue;
+            }
+
             // We have arrived at our destination! Attempt to process the claim!
-            commands.entity(transit.claim).despawn();
+            if let Ok(demand) = demand_query.get(claim) {
+                commands.trigger(OrderFulfilled {
+                    satellite: transit.route[0],
+                    resource: *resource,
+                    priority: demand.priority,
+                });
+                if let Ok(orbital_position) = planet_query.get(transit.route[0]) {
+                    commands.trigger(PlaySfx::At(
+                        SfxKey::Delivery,
+                        orbital_position.get_euclidean_position(),
+                    ));
+                }
+            }
+            commands.entity(claim).despawn();
             commands.entity(entity).despawn();
         } else {
             // We are part way to our destination... verify our path's integrity
@@ -383,33 +909,157 @@ fn process_transit_stops(
                     .insert(PendingDeparture);
             } else {
                 // Remove the claim
-                if let Ok(mut demand) = demand_query.get_mut(transit.claim) {
+                if let Some(Ok(mut demand)) = transit.claim.map(|claim| demand_query.get_mut(claim))
+                {
                     demand.claim = None;
                 }
 
-                // Get the local resource container
-                let container_entity = transit.route[0];
-
-                // Get the resource container on this hub (if it exists!)
-                if let Ok(mut container) = container_query.get_mut(container_entity) {
-                    // Attempt to put this resource in the container, otherwise destroy it
-                    if container.storage_count < container.storage_size {
-                        container.storage_count += 1;
-
-                        // Remove the transit and add in the storage
-                        commands
-                            .entity(entity)
-                            .remove::<GameResourceInTransit>()
-                            .insert(GameResourceInStorage {
-                                satellite: container_entity,
-                            });
-                    } else {
-                        commands.entity(entity).despawn();
-                    }
-                } else {
-                    commands.entity(entity).despawn();
-                }
+                // Put the cargo in the local container, or let the overflow policy decide
+                store_cargo(
+                    &mut commands,
+                    *policy,
+                    entity,
+                    transit.route[0],
+                    &mut container_query,
+                    &connection_query,
+                    &warehouse_query,
+                );
+            }
+        }
+    }
+}
+
+/// Put cargo that stopped at `satellite` into its storage.
+/// If there is no room, the overflow policy decides what happens to it.
+fn store_cargo(
+    commands: &mut Commands,
+    policy: OverflowPolicy,
+    cargo: Entity,
+    satellite: Entity,
+    container_query: &mut Query<&mut ResourceContainer>,
+    connection_query: &Query<
+        (&ConnectionAnchor, &ConnectionTarget),
+        Without<ConnectionUnderConstruction>,
+    >,
+    warehouse_query: &Query<(), With<Warehouse>>,
+) {
+    if let Ok(mut container) = container_query.get_mut(satellite) {
+        if container.has_room() {
+            container.storage_count += 1;
+            commands
+                .entity(cargo)
+                .remove::<GameResourceInTransit>()
+                .insert(GameResourceInStorage { satellite });
+            return;
+        }
+    }
+
+    let spill = match policy {
+        OverflowPolicy::Spill => spill_route(
+            satellite,
+            false,
+            connection_query,
+            container_query,
+            warehouse_query,
+        ),
+        _ => None,
+    };
+    match (policy, spill) {
+        (_, Some(route)) => {
+            commands.entity(cargo).insert((
+                GameResourceInTransit {
+                    route,
+                    claim: None,
+                    position: 0.0,
+                    processing: None,
+                },
+                UpdateProgress,
+            ));
+        }
+        (OverflowPolicy::Destroy, _) => {
+            commands.trigger(CargoLost);
+            commands.entity(cargo).despawn();
+        }
+        // Cargo with nowhere to spill to waits, like rejected cargo.
+        _ => {
+            commands
+                .entity(cargo)
+                .remove::<GameResourceInTransit>()
+                .insert(WaitingForRoom { satellite });
+        }
+    }
+}
+
+/// The route to the nearest satellite connected to `from` that has room in its storage.
+/// Warehouses are preferred, and with `warehouses_only` nothing else will do.
+fn spill_route(
+    from: Entity,
+    warehouses_only: bool,
+    connection_query: &Query<
+        (&ConnectionAnchor, &ConnectionTarget),
+        Without<ConnectionUnderConstruction>,
+    >,
+    container_query: &Query<&mut ResourceContainer>,
+    warehouse_query: &Query<(), With<Warehouse>>,
+) -> Option<Vec<Entity>> {
+    let mut visited = HashSet::from([from]);
+    let mut open_list = VecDeque::from([vec![from]]);
+    let mut nearest = None;
+
+    while let Some(path) = open_list.pop_front() {
+        let current = path[path.len() - 1];
+        let has_room = container_query
+            .get(current)
+            .is_ok_and(|container| container.has_room());
+        if current != from && has_room {
+            if warehouse_query.contains(current) {
+                return Some(path);
+            }
+            if nearest.is_none() && !warehouses_only {
+                nearest = Some(path.clone());
+            }
+        }
+
+        for (anchor, target) in connection_query {
+            let ConnectionTarget::Satellite(target) = target else {
+                continue;
+            };
+            let next = if anchor.satellite == current {
+                *target
+            } else if *target == current {
+                anchor.satellite
+            } else {
+                continue;
+            };
+            if visited.insert(next) {
+                let mut new_path = path.clone();
+                new_path.push(next);
+                open_list.push_back(new_path);
             }
         }
     }
+
+    nearest
+}
+
+/// Cargo turned away by a full satellite goes into its storage as soon as there is room.
+fn store_waiting_cargo(
+    mut commands: Commands,
+    waiting_query: Query<(Entity, &WaitingForRoom)>,
+    mut container_query: Query<&mut ResourceContainer>,
+) {
+    for (entity, waiting) in &waiting_query {
+        let Ok(mut container) = container_query.get_mut(waiting.satellite) else {
+            continue;
+        };
+        if container.has_room() {
+            container.storage_count += 1;
+            commands
+                .entity(entity)
+                .remove::<WaitingForRoom>()
+                .insert(GameResourceInStorage {
+                    satellite: waiting.satellite,
+                });
+        }
+    }
 }
@@ -0,0 +1,48 @@
+//! Seeded randomness for the simulation, so a run can be replayed from its seed.
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<RunSeed>();
+    app.init_resource::<GameRng>();
+    app.add_systems(OnEnter(Screen::Playing), seed_game_rng);
+}
+
+/// The seed to use for the next run. A fresh random seed is picked if this is empty.
+/// The seed is used up once the run starts.
+#[derive(Resource, Debug, Default)]
+pub struct RunSeed(pub Option<u64>);
+
+/// The random number generator for everything that happens in a run.
+/// Effects that don't change the outcome, like sounds, can use any generator.
+#[derive(Resource, Debug)]
+pub struct GameRng {
+    pub seed: u64,
+    pub rng: StdRng,
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::from_seed(0)
+    }
+}
+
+impl GameRng {
+    fn from_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+fn seed_game_rng(mut run_seed: ResMut<RunSeed>, mut game_rng: ResMut<GameRng>) {
+    let seed = run_seed
+        .0
+        .take()
+        .unwrap_or_else(|| rand::thread_rng().gen());
+    *game_rng = GameRng::from_seed(seed);
+}
@@ -4,11 +4,16 @@ use bevy::{
 };
 
 use crate::{
-    game::interaction::{InteractionState, MousePosition},
+    game::{
+        assets::SfxKey,
+        audio::sfx::PlaySfx,
+        interaction::{InteractionState, MousePosition},
+        research::{ResearchModifiers, SelectedLink},
+    },
     AppSet,
 };
 
-use super::planet::{OrbitalPosition, Planet};
+use super::{level::Sun, planet::OrbitalPosition};
 
 #[derive(Event, Debug)]
 pub struct InitiateConnection(pub Entity);
@@ -44,6 +49,99 @@ impl ConnectionProperties {
 #[reflect(Component)]
 pub struct ConnectionUnderConstruction;
 
+/// What a connection trades off between reach and speed. Only standard links are
+/// available until research unlocks the others.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[reflect(Component)]
+pub enum LinkKind {
+    #[default]
+    Standard,
+    /// Faster ships over a shorter reach.
+    Express,
+    /// Longer reach for slower ships.
+    LongHaul,
+}
+
+impl LinkKind {
+    pub const ALL: [Self; 3] = [LinkKind::Standard, LinkKind::Express, LinkKind::LongHaul];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LinkKind::Standard => "Standard",
+            LinkKind::Express => "Express",
+            LinkKind::LongHaul => "Long-haul",
+        }
+    }
+
+    pub fn range_factor(&self) -> f32 {
+        match self {
+            LinkKind::Standard => 1.0,
+            LinkKind::Express => 0.75,
+            LinkKind::LongHaul => 1.5,
+        }
+    }
+
+    pub fn speed_factor(&self) -> f32 {
+        match self {
+            LinkKind::Standard => 1.0,
+            LinkKind::Express => 1.5,
+            LinkKind::LongHaul => 0.75,
+        }
+    }
+}
+
+/// Where the ends of a connection are right now, or `None` if one of them is gone.
+pub fn connection_ends(
+    anchor: &ConnectionAnchor,
+    target: &ConnectionTarget,
+    position_query: &Query<&OrbitalPosition>,
+) -> Option<(Vec3, Vec3)> {
+    let start = position_query
+        .get(anchor.satellite)
+        .ok()?
+        .get_euclidean_position();
+    let end = match *target {
+        ConnectionTarget::Satellite(satellite) => {
+            position_query.get(satellite).ok()?.get_euclidean_position()
+        }
+        ConnectionTarget::Position(position) => position,
+    };
+    Some((start, end))
+}
+
+/// How long a connection is right now, or `None` if one of its ends is gone.
+pub fn connection_length(
+    anchor: &ConnectionAnchor,
+    target: &ConnectionTarget,
+    position_query: &Query<&OrbitalPosition>,
+) -> Option<f32> {
+    connection_ends(anchor, target, position_query).map(|(start, end)| start.distance(end))
+}
+
+/// Whether a connection between `start` and `end` breaks, for being out of range
+/// or for crossing the sun.
+pub fn connection_breaks(
+    properties: &ConnectionProperties,
+    start: Vec3,
+    end: Vec3,
+    sun: &Sun,
+) -> bool {
+    if !properties.is_valid_range_sqr((end - start).length_squared()) {
+        return true;
+    }
+
+    // Check to see if we are intersecting the sun!
+    // Line SDF: https://www.shadertoy.com/view/Wlfyzl
+    // Since the sun is at 0,0 we can make some assumptions
+    let ba = end.xy() - start.xy();
+    let pa = -start.xy();
+
+    let h = f32::clamp(pa.dot(ba) / ba.dot(ba), 0.0, 1.0);
+    let dist_vec = pa - h * ba;
+
+    dist_vec.length_squared() < sun.radius * sun.radius
+}
+
 #[derive(Resource, Default)]
 pub struct ConnectionConfig {
     pub range: f32,
@@ -65,6 +163,9 @@ pub(super) fn plugin(app: &mut App) {
 fn initiate_connection(
     trigger: Trigger<InitiateConnection>,
     mouse_pos: Res<MousePosition>,
+    config: Res<ConnectionConfig>,
+    modifiers: Res<ResearchModifiers>,
+    selected: Res<SelectedLink>,
     mut commands: Commands,
 ) {
     commands.spawn((
@@ -76,8 +177,9 @@ fn initiate_connection(
         ConnectionProperties {
             color: Color::Srgba(WHITE),
             invalid_color: Color::Srgba(RED),
-            range: 200.0,
+            range: modifiers.connection_range(&config, selected.0),
         },
+        selected.0,
         ConnectionUnderConstruction,
         InteractionState::default(),
     ));
@@ -92,7 +194,7 @@ fn update_connections(
     }
 }
 
-fn check_for_invalid_connections(
+pub fn check_for_invalid_connections(
     mut commands: Commands,
     connection_query: Query<(
         &ConnectionAnchor,
@@ -100,7 +202,8 @@ fn check_for_invalid_connections(
         &ConnectionProperties,
         Entity,
     )>,
-    planet_query: Query<&OrbitalPosition, With<Planet>>,
+    planet_query: Query<&OrbitalPosition>,
+    sun: Res<Sun>,
 ) {
     for (anchor, target, properties, entity) in &connection_query {
         // Check the length of the connection
@@ -117,27 +220,10 @@ fn check_for_invalid_connections(
                 ConnectionTarget::Position(pos) => pos,
             };
 
-            if !properties.is_valid_range_sqr((end - start).length_squared()) {
-                if let ConnectionTarget::Satellite(_) = target {
-                    commands.entity(entity).despawn();
-                    continue;
-                }
-            }
-
-            // Check to see if we are intersecting the sun!
-            // Line SDF: https://www.shadertoy.com/view/Wlfyzl
-            // Since the sun is at 0,0 we can make some assumptions
-            let ba = end.xy() - start.xy();
-            let pa = -start.xy();
-
-            let h = f32::clamp(pa.dot(ba) / ba.dot(ba), 0.0, 1.0);
-            let dist_vec = pa - h * ba;
-
-            let sun_radius = 20.0;
-            if dist_vec.length_squared() < sun_radius * sun_radius {
-                if let ConnectionTarget::Satellite(_) = target {
+            if let ConnectionTarget::Satellite(_) = target {
+                if connection_breaks(properties, start, end, &sun) {
                     commands.entity(entity).despawn();
-                    continue;
+                    commands.trigger(PlaySfx::At(SfxKey::LinkBreak, start.lerp(end, 0.5)));
                 }
             }
         }
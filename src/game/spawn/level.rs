@@ -1,31 +1,170 @@
 //! Spawn the main level by triggering other observers.
 
-use bevy::prelude::*;
+use std::fmt;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+};
 use bevy_vector_shapes::{painter::ShapePainter, shapes::DiscPainter};
+use serde::{Deserialize, Serialize};
 
-use crate::screen::Screen;
+use crate::{
+    game::{
+        alignment::AlignmentEffect,
+        assets::{HandleMap, LevelKey},
+        director::DirectorConfig,
+        objective::Objective,
+        resource::OverflowPolicy,
+        system_event::EventSchedule,
+        tutorial::TutorialStep,
+    },
+    screen::Screen,
+};
 
-use super::planet::SpawnPlanets;
+use super::planet::{SatelliteLayout, SpawnPlanet};
 
 pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<Level>();
+    app.register_asset_loader(LevelLoader);
+    app.init_resource::<Sun>();
+    app.add_systems(OnEnter(Screen::Playing), reset_sun);
     app.observe(spawn_level);
     app.add_systems(Update, draw_level.run_if(in_state(Screen::Playing)));
 }
 
 #[derive(Event, Debug)]
-pub struct SpawnLevel;
+pub struct SpawnLevel(pub LevelKey);
+
+/// How large the sun is unless something makes it flare up.
+pub const SUN_RADIUS: f32 = 20.0;
+
+/// The sun at the centre of every level. Connections can't pass through it.
+#[derive(Resource, Debug)]
+pub struct Sun {
+    pub radius: f32,
+}
+
+impl Default for Sun {
+    fn default() -> Self {
+        Self { radius: SUN_RADIUS }
+    }
+}
+
+/// A level layout, loaded from a `.level.ron` file.
+#[derive(Asset, TypePath, Debug, Serialize, Deserialize)]
+pub struct Level {
+    pub name: String,
+    /// Every objective has to be met to complete the level.
+    #[serde(default)]
+    pub objectives: Vec<Objective>,
+    /// The scores needed for a second and third star.
+    #[serde(default)]
+    pub star_scores: [u32; 2],
+    pub satellites: Vec<SatelliteLayout>,
+    /// Endless levels keep changing while they're played.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub director: Option<DirectorConfig>,
+    /// Scripted steps that walk the player through the level.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tutorial: Vec<TutorialStep>,
+    /// Levels with a schedule are interrupted by random events, like solar flares.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub events: Option<EventSchedule>,
+    /// Bonuses that apply while satellites line up.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alignments: Vec<AlignmentEffect>,
+    /// What happens to cargo that stops at a full satellite.
+    #[serde(default)]
+    pub overflow: OverflowPolicy,
+}
+
+impl Level {
+    /// Completing the level earns one star, high enough scores earn up to three.
+    pub fn stars(&self, score: u32) -> u32 {
+        1 + self
+            .star_scores
+            .iter()
+            .filter(|threshold| score >= **threshold)
+            .count() as u32
+    }
+}
+
+#[derive(Default)]
+struct LevelLoader;
+
+#[derive(Debug)]
+enum LevelLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+impl fmt::Display for LevelLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelLoaderError::Io(error) => write!(f, "could not read level: {error}"),
+            LevelLoaderError::Ron(error) => write!(f, "could not parse level: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for LevelLoaderError {}
+
+impl AssetLoader for LevelLoader {
+    type Asset = Level;
+    type Settings = ();
+    type Error = LevelLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Level, LevelLoaderError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(LevelLoaderError::Io)?;
+        ron::de::from_bytes(&bytes).map_err(LevelLoaderError::Ron)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+/// Levels are loaded on the loading screen, so they are always available here.
+fn spawn_level(
+    trigger: Trigger<SpawnLevel>,
+    mut commands: Commands,
+    level_handles: Res<HandleMap<LevelKey>>,
+    levels: Res<Assets<Level>>,
+) {
+    let Some(level) = levels.get(&level_handles[&trigger.event().0]) else {
+        return;
+    };
+
+    commands.insert_resource(level.overflow);
+    for (index, layout) in level.satellites.iter().enumerate() {
+        commands.trigger(SpawnPlanet {
+            index,
+            layout: layout.clone(),
+        });
+    }
+}
 
-fn spawn_level(_trigger: Trigger<SpawnLevel>, mut commands: Commands) {
-    commands.trigger(SpawnPlanets);
+fn reset_sun(mut sun: ResMut<Sun>) {
+    *sun = Sun::default();
 }
 
-fn draw_level(mut painter: ShapePainter) {
+fn draw_level(mut painter: ShapePainter, sun: Res<Sun>) {
     painter.hollow = true;
     painter.thickness = 4.0;
     painter.set_color(Color::srgb(0.9, 0.9, 0.2));
-    painter.circle(20.0);
+    painter.circle(sun.radius);
 
     painter.hollow = false;
     painter.set_color(Color::srgb(1.0, 0.5, 0.0));
-    painter.circle(16.0);
+    painter.circle(sun.radius - 4.0);
 }
@@ -1,25 +1,84 @@
-use bevy::{color::palettes::css::WHITE, prelude::*};
+use bevy::{color::palettes::css::WHITE, ecs::system::EntityCommands, prelude::*};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 //
 use crate::{
     game::{
         interaction::InteractionState,
-        resource::{GameResource, ResourceConsumer, ResourceContainer, ResourceSpawner},
+        resource::{
+            GameResource, Recipe, Refinery, ResourceConsumer, ResourceContainer, ResourceSpawner,
+            Warehouse, WAREHOUSE_STORAGE_SIZE,
+        },
     },
     screen::Screen,
 };
 
 pub(super) fn plugin(app: &mut App) {
-    app.observe(spawn_planets);
+    app.register_type::<SatelliteIndex>();
+    app.observe(spawn_planet);
 }
 
+/// Spawn a single satellite from its level layout.
 #[derive(Event, Debug)]
-pub struct SpawnPlanets;
+pub struct SpawnPlanet {
+    /// Position in the level file, used to name the satellite and by tutorial steps.
+    pub index: usize,
+    pub layout: SatelliteLayout,
+}
+
+/// How a satellite is described in a level file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SatelliteLayout {
+    pub radius: f32,
+    pub orbit_radius: f32,
+    /// Starting angle around the sun, in radians.
+    pub orbit_position: f32,
+    /// Radians per second.
+    pub orbit_speed: f32,
+    pub storage_size: usize,
+    pub role: SatelliteRole,
+    /// Ships leaving a refinery turn one kind of cargo into another on the way.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refinery: Option<Recipe>,
+}
+
+impl SatelliteLayout {
+    /// A satellite with a random size, starting angle and speed, for levels made up at runtime.
+    pub fn random(rng: &mut impl Rng, orbit_radius: f32, role: SatelliteRole) -> Self {
+        Self {
+            radius: rng.gen_range(3.0..16.0),
+            orbit_radius,
+            orbit_position: rng.gen_range(0.0..std::f32::consts::TAU),
+            // Outer satellites take longer to go around.
+            orbit_speed: 12.0 / orbit_radius * rng.gen_range(0.8..1.2),
+            storage_size: 6,
+            role,
+            refinery: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SatelliteRole {
+    /// Only stores and passes on cargo.
+    Relay,
+    Spawner(GameResource),
+    /// Starts out demanding the listed cargo.
+    Consumer(Vec<GameResource>),
+    /// Stores lots of cargo, as a buffer for spawners and overflowing satellites.
+    Warehouse,
+}
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
 #[reflect(Component)]
 pub struct Planet;
 
+/// Where a satellite appears in its level file, so scripted steps can refer to it.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct SatelliteIndex(pub usize);
+
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
 pub struct OrbitalPosition {
@@ -48,147 +107,59 @@ pub struct SatelliteProperties {
     pub color: Color,
 }
 
-fn spawn_planets(_trigger: Trigger<SpawnPlanets>, mut commands: Commands) {
-    commands.spawn((
-        Name::new("Planet"),
+fn spawn_planet(trigger: Trigger<SpawnPlanet>, mut commands: Commands) {
+    let SpawnPlanet { index, layout } = trigger.event();
+    let mut entity = commands.spawn((
+        Name::new(format!("Planet {}", index + 1)),
         Planet,
+        SatelliteIndex(*index),
         SatelliteProperties {
-            radius: 4.0,
+            radius: layout.radius,
             color: Color::Srgba(WHITE),
         },
         StateScoped(Screen::Playing),
         InteractionState::default(),
-        ResourceConsumer {
-            demands: vec![
-                GameResource::Material,
-                GameResource::Material,
-                GameResource::Material,
-            ],
+        OrbitalMovement {
+            speed: layout.orbit_speed,
         },
-        OrbitalMovement { speed: 0.2 },
         OrbitalPosition {
-            position: 1.23,
-            radius: 64.0,
+            position: layout.orbit_position,
+            radius: layout.orbit_radius,
         },
         ResourceContainer {
             storage_count: 0,
-            storage_size: 6,
+            storage_size: layout.storage_size,
+            extra_storage: 0,
         },
     ));
 
-    commands.spawn((
-        Name::new("Planet"),
-        Planet,
-        SatelliteProperties {
-            radius: 8.0,
-            color: Color::Srgba(WHITE),
-        },
-        StateScoped(Screen::Playing),
-        InteractionState::default(),
-        OrbitalMovement { speed: 0.1 },
-        OrbitalPosition {
-            position: 4.22,
-            radius: 128.0,
-        },
-        ResourceContainer {
-            storage_count: 0,
-            storage_size: 6,
-        },
-    ));
-
-    commands.spawn((
-        Name::new("Planet"),
-        Planet,
-        SatelliteProperties {
-            radius: 18.0,
-            color: Color::Srgba(WHITE),
-        },
-        StateScoped(Screen::Playing),
-        InteractionState::default(),
-        OrbitalMovement { speed: 0.07 },
-        OrbitalPosition {
-            position: 5.22,
-            radius: 200.0,
-        },
-        ResourceSpawner {
-            spawn_type: GameResource::Material,
-        },
-        ResourceContainer {
-            storage_count: 0,
-            storage_size: 6,
-        },
-    ));
-
-    commands.spawn((
-        Name::new("Planet"),
-        Planet,
-        SatelliteProperties {
-            radius: 12.0,
-            color: Color::Srgba(WHITE),
-        },
-        StateScoped(Screen::Playing),
-        InteractionState::default(),
-        ResourceConsumer {
-            demands: vec![
-                GameResource::Material,
-                GameResource::Material,
-                GameResource::Material,
-            ],
-        },
-        OrbitalMovement { speed: 0.05 },
-        OrbitalPosition {
-            position: 0.3,
-            radius: 256.0,
-        },
-        ResourceContainer {
-            storage_count: 0,
-            storage_size: 6,
-        },
-    ));
-
-    commands.spawn((
-        Name::new("Planet"),
-        Planet,
-        SatelliteProperties {
-            radius: 3.0,
-            color: Color::Srgba(WHITE),
-        },
-        StateScoped(Screen::Playing),
-        InteractionState::default(),
-        OrbitalMovement { speed: 0.025 },
-        OrbitalPosition {
-            position: 5.4,
-            radius: 300.0,
-        },
-        ResourceContainer {
-            storage_count: 0,
-            storage_size: 6,
-        },
-        ResourceConsumer {
-            demands: Vec::new(),
-        },
-    ));
+    apply_role(&mut entity, &layout.role);
+    if let Some(recipe) = layout.refinery {
+        entity.insert(Refinery(recipe));
+    }
+}
 
-    commands.spawn((
-        Name::new("Planet"),
-        Planet,
-        SatelliteProperties {
-            radius: 3.0,
-            color: Color::Srgba(WHITE),
-        },
-        StateScoped(Screen::Playing),
-        InteractionState::default(),
-        OrbitalMovement { speed: 0.02 },
-        OrbitalPosition {
-            position: 5.5,
-            radius: 336.0,
-        },
-        ResourceContainer {
-            storage_count: 0,
-            storage_size: 6,
-        },
-        ResourceSpawner {
-            spawn_type: GameResource::Material,
-        },
-    ));
+/// Give a satellite the components for `role`, replacing the ones for any role it had before.
+pub fn apply_role(entity: &mut EntityCommands, role: &SatelliteRole) {
+    entity.remove::<(ResourceSpawner, ResourceConsumer, Warehouse)>();
+    match role {
+        SatelliteRole::Relay => (),
+        SatelliteRole::Warehouse => {
+            entity.insert(Warehouse).add(|mut entity: EntityWorldMut| {
+                if let Some(mut container) = entity.get_mut::<ResourceContainer>() {
+                    container.storage_size = container.storage_size.max(WAREHOUSE_STORAGE_SIZE);
+                }
+            });
+        }
+        SatelliteRole::Spawner(spawn_type) => {
+            entity.insert(ResourceSpawner {
+                spawn_type: *spawn_type,
+            });
+        }
+        SatelliteRole::Consumer(demands) => {
+            entity.insert(ResourceConsumer {
+                demands: demands.clone(),
+            });
+        }
+    }
 }
@@ -0,0 +1,330 @@
+//! Relay stations the player buys and places, either fixed in space or riding a planet's
+//! L4 or L5 Lagrange point. They take part in the network like any satellite, with a little storage,
+//! so connections can bridge gaps that are too wide for a single hop. They have to be
+//! researched before the first one can be built.
+
+use std::f32::consts::{FRAC_PI_3, PI};
+
+use bevy::{color::palettes::css::WHITE, prelude::*};
+use bevy_vector_shapes::{
+    painter::ShapePainter,
+    shapes::{DiscPainter, RegularPolygonPainter},
+};
+
+use super::{
+    assets::SfxKey,
+    audio::sfx::PlaySfx,
+    editor::editing,
+    interaction::{spawn_connections, InteractionGate, InteractionState, MousePosition},
+    ledger::Ledger,
+    movement::apply_orbital_movement,
+    palette::GamePalette,
+    research::ResearchModifiers,
+    resource::ResourceContainer,
+    spawn::{
+        level::Sun,
+        planet::{OrbitalMovement, OrbitalPosition, Planet, SatelliteProperties},
+    },
+};
+use crate::{
+    screen::Screen,
+    settings::{BindingAction, Settings},
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<StationBuilder>();
+
+    app.add_systems(OnEnter(Screen::Playing), reset_station_builder);
+    app.observe(toggle_station_builder);
+    app.add_systems(
+        Update,
+        (
+            toggle_station_builder_key.in_set(AppSet::RecordInput),
+            // Connections see the builder still active on the frame a station is placed.
+            place_station
+                .in_set(AppSet::Update)
+                .after(spawn_connections)
+                .run_if(not(editing)),
+            follow_lagrange_points
+                .in_set(AppSet::Update)
+                .after(apply_orbital_movement),
+        )
+            .run_if(in_state(Screen::Playing)),
+    );
+    app.add_systems(
+        Update,
+        draw_stations
+            .in_set(AppSet::Render)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// What a relay station costs to build.
+pub const STATION_COST: i32 = 250;
+
+/// How much cargo a relay station can hold.
+const STATION_STORAGE_SIZE: usize = 2;
+
+const STATION_RADIUS: f32 = 4.0;
+
+/// How close the mouse has to be to a Lagrange point for a station to ride it.
+const LAGRANGE_SNAP_DISTANCE: f32 = 16.0;
+
+/// Stations can't be built this close to the sun.
+const SUN_CLEARANCE: f32 = 12.0;
+
+/// Whether the player is placing a relay station.
+#[derive(Resource, Debug, Default)]
+pub struct StationBuilder {
+    pub active: bool,
+}
+
+/// Start or stop placing a relay station.
+#[derive(Event, Debug)]
+pub struct ToggleStationBuilder;
+
+/// A relay station built by the player.
+#[derive(Component, Debug)]
+pub struct RelayStation;
+
+/// The two stable points on a planet's orbit, 60 degrees ahead of and behind it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LagrangePoint {
+    L4,
+    L5,
+}
+
+impl LagrangePoint {
+    pub const ALL: [Self; 2] = [LagrangePoint::L4, LagrangePoint::L5];
+
+    /// The orbital position of this point for a planet at `orbit` moving at `speed`.
+    /// L4 leads the planet and L5 trails it.
+    pub fn position(&self, orbit: &OrbitalPosition, speed: f32) -> OrbitalPosition {
+        let ahead = FRAC_PI_3 * speed.signum();
+        OrbitalPosition {
+            position: match self {
+                LagrangePoint::L4 => orbit.position + ahead,
+                LagrangePoint::L5 => orbit.position - ahead,
+            },
+            radius: orbit.radius,
+        }
+    }
+}
+
+/// Keeps a station on a planet's Lagrange point as the planet orbits.
+#[derive(Component, Debug)]
+pub struct LagrangeAnchor {
+    pub planet: Entity,
+    pub point: LagrangePoint,
+}
+
+fn reset_station_builder(mut builder: ResMut<StationBuilder>) {
+    builder.active = false;
+}
+
+fn toggle_station_builder(
+    _trigger: Trigger<ToggleStationBuilder>,
+    modifiers: Res<ResearchModifiers>,
+    mut builder: ResMut<StationBuilder>,
+) {
+    builder.active = !builder.active && modifiers.relay_stations;
+}
+
+fn toggle_station_builder_key(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+) {
+    if input.just_pressed(settings.key_bindings.get(BindingAction::BuildStation)) {
+        commands.trigger(ToggleStationBuilder);
+    }
+}
+
+/// Where a station would go if it were placed at `point`: on the nearest Lagrange point
+/// in reach, otherwise fixed where it is.
+fn placement(
+    point: Vec2,
+    planet_query: &Query<(Entity, &OrbitalPosition, &OrbitalMovement), With<Planet>>,
+) -> (OrbitalPosition, Option<LagrangeAnchor>) {
+    let nearest = planet_query
+        .iter()
+        .flat_map(|(planet, orbit, movement)| {
+            LagrangePoint::ALL.map(|point| {
+                (
+                    point.position(orbit, movement.speed),
+                    LagrangeAnchor { planet, point },
+                )
+            })
+        })
+        .map(|(orbit, anchor)| {
+            let distance = orbit.get_euclidean_position().xy().distance(point);
+            (orbit, anchor, distance)
+        })
+        .filter(|(.., distance)| *distance < LAGRANGE_SNAP_DISTANCE)
+        .min_by(|a, b| a.2.total_cmp(&b.2));
+
+    match nearest {
+        Some((orbit, anchor, _)) => (orbit, Some(anchor)),
+        // Positions are measured clockwise from straight up.
+        None => (
+            OrbitalPosition {
+                position: point.x.atan2(point.y),
+                radius: point.length(),
+            },
+            None,
+        ),
+    }
+}
+
+/// Whether a station at `orbit` would be too close to the sun or on top of another satellite.
+fn blocked(
+    orbit: &OrbitalPosition,
+    sun: &Sun,
+    satellite_query: &Query<(&OrbitalPosition, &SatelliteProperties)>,
+) -> bool {
+    let position = orbit.get_euclidean_position().xy();
+    orbit.radius < sun.radius + SUN_CLEARANCE
+        || satellite_query.iter().any(|(other, properties)| {
+            other.get_euclidean_position().xy().distance(position)
+                < properties.radius + STATION_RADIUS
+        })
+}
+
+/// Whether clicks on the board go to placing a relay station. This includes the frame
+/// after a station is placed, when the new station first shows as pressed, so the same
+/// click doesn't also start a connection.
+pub fn building_station(builder: Res<StationBuilder>) -> bool {
+    builder.active || builder.is_changed()
+}
+
+fn place_station(
+    mut commands: Commands,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    mouse_position: Res<MousePosition>,
+    gate: Res<InteractionGate>,
+    sun: Res<Sun>,
+    mut builder: ResMut<StationBuilder>,
+    mut ledger: ResMut<Ledger>,
+    planet_query: Query<(Entity, &OrbitalPosition, &OrbitalMovement), With<Planet>>,
+    satellite_query: Query<(&OrbitalPosition, &SatelliteProperties)>,
+    station_query: Query<(), With<RelayStation>>,
+    ui_query: Query<&Interaction>,
+) {
+    if !builder.active || !mouse_button.just_pressed(MouseButton::Left) {
+        return;
+    }
+    // Clicks on the HUD aren't meant for the board.
+    if ui_query
+        .iter()
+        .any(|interaction| *interaction != Interaction::None)
+    {
+        return;
+    }
+    if gate.satellites.is_some() || ledger.money < STATION_COST {
+        return;
+    }
+
+    let (orbit, anchor) = placement(mouse_position.get_pos_3d().xy(), &planet_query);
+    if blocked(&orbit, &sun, &satellite_query) {
+        return;
+    }
+
+    let position = orbit.get_euclidean_position();
+    let mut station = commands.spawn((
+        Name::new(format!(
+            "Relay Station {}",
+            station_query.iter().count() + 1
+        )),
+        RelayStation,
+        SatelliteProperties {
+            radius: STATION_RADIUS,
+            color: Color::Srgba(WHITE),
+        },
+        InteractionState::default(),
+        orbit,
+        ResourceContainer {
+            storage_count: 0,
+            storage_size: STATION_STORAGE_SIZE,
+            extra_storage: 0,
+        },
+        StateScoped(Screen::Playing),
+    ));
+    if let Some(anchor) = anchor {
+        station.insert(anchor);
+    }
+
+    ledger.money -= STATION_COST;
+    builder.active = false;
+    commands.trigger(PlaySfx::At(SfxKey::ButtonPress, position));
+}
+
+fn follow_lagrange_points(
+    mut commands: Commands,
+    mut station_query: Query<(Entity, &LagrangeAnchor, &mut OrbitalPosition), With<RelayStation>>,
+    planet_query: Query<(&OrbitalPosition, &OrbitalMovement), Without<RelayStation>>,
+) {
+    for (station, anchor, mut orbit) in &mut station_query {
+        match planet_query.get(anchor.planet) {
+            Ok((planet_orbit, movement)) => {
+                *orbit = anchor.point.position(planet_orbit, movement.speed);
+            }
+            // A station whose planet is gone stays where it is.
+            Err(_) => {
+                commands.entity(station).remove::<LagrangeAnchor>();
+            }
+        }
+    }
+}
+
+fn draw_stations(
+    mut painter: ShapePainter,
+    palette: Res<GamePalette>,
+    builder: Res<StationBuilder>,
+    mouse_position: Res<MousePosition>,
+    ledger: Res<Ledger>,
+    sun: Res<Sun>,
+    station_query: Query<&OrbitalPosition, With<RelayStation>>,
+    planet_query: Query<(Entity, &OrbitalPosition, &OrbitalMovement), With<Planet>>,
+    satellite_query: Query<(&OrbitalPosition, &SatelliteProperties)>,
+) {
+    painter.hollow = true;
+    painter.thickness = 1.0;
+    painter.roundness = 0.0;
+    painter.set_color(palette.idle);
+    for orbit in &station_query {
+        painter.set_translation(orbit.get_euclidean_position());
+        painter.set_rotation(Quat::from_rotation_z(PI / 4.0));
+        painter.ngon(4.0, STATION_RADIUS * 2.0);
+    }
+    painter.set_rotation(Quat::IDENTITY);
+
+    if !builder.active {
+        painter.set_translation(Vec3::ZERO);
+        return;
+    }
+
+    // Show every Lagrange point a station can ride, and where the station would go.
+    painter.set_color(palette.range_guide);
+    for (_, orbit, movement) in &planet_query {
+        for point in LagrangePoint::ALL {
+            painter.set_translation(
+                point
+                    .position(orbit, movement.speed)
+                    .get_euclidean_position(),
+            );
+            painter.ngon(3.0, STATION_RADIUS * 1.5);
+        }
+    }
+
+    let (orbit, _) = placement(mouse_position.get_pos_3d().xy(), &planet_query);
+    let color = if ledger.money < STATION_COST || blocked(&orbit, &sun, &satellite_query) {
+        palette.out_of_range
+    } else {
+        palette.range_guide
+    };
+    painter.set_color(color);
+    painter.set_translation(orbit.get_euclidean_position());
+    painter.circle(STATION_RADIUS * 2.0);
+    painter.set_translation(Vec3::ZERO);
+}
@@ -0,0 +1,140 @@
+//! Record how a run goes so it can be summarised once it ends.
+
+use bevy::{prelude::*, utils::HashMap};
+
+use super::{
+    clock::GameClock,
+    ledger::{Ledger, OrderFailed, OrderFulfilled},
+    resource::CargoHopped,
+};
+use crate::{screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<RunStatistics>();
+
+    app.add_systems(OnEnter(Screen::Playing), reset_statistics);
+    app.add_systems(OnExit(Screen::Playing), record_sample);
+    app.observe(record_delivery);
+    app.observe(record_failure);
+    app.observe(record_traffic);
+    app.add_systems(
+        Update,
+        record_sample
+            .run_if(sample_due)
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// Seconds of game time between samples of the running totals.
+const SAMPLE_INTERVAL_SECS: f32 = 5.0;
+
+/// The running totals at one point in a run.
+#[derive(Debug, Clone, Copy)]
+pub struct StatSample {
+    /// Game time in seconds since the level started.
+    pub elapsed: f32,
+    pub deliveries: u32,
+    pub money: i32,
+}
+
+/// Orders delivered and failed during one cycle.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CycleTally {
+    pub deliveries: u32,
+    pub failures: u32,
+}
+
+/// Everything recorded during the current or most recent run.
+#[derive(Resource, Debug, Default)]
+pub struct RunStatistics {
+    pub samples: Vec<StatSample>,
+    /// One tally per cycle, in order.
+    pub cycles: Vec<CycleTally>,
+    /// Failed orders, keyed by satellite name.
+    pub failures_by_satellite: HashMap<String, u32>,
+    /// Cargo carried along each connection, keyed by the names of both ends.
+    pub connection_traffic: HashMap<(String, String), u32>,
+}
+
+impl RunStatistics {
+    /// The tally for the cycle `clock` is in, adding any cycles that have passed since the last one.
+    fn current_cycle(&mut self, clock: &GameClock) -> &mut CycleTally {
+        let cycle = clock.cycle() as usize;
+        if self.cycles.len() <= cycle {
+            self.cycles.resize(cycle + 1, CycleTally::default());
+        }
+        &mut self.cycles[cycle]
+    }
+
+    /// The `count` largest entries of `values`, largest first.
+    pub fn top<K: Clone + Ord>(values: &HashMap<K, u32>, count: usize) -> Vec<(K, u32)> {
+        let mut entries: Vec<_> = values.iter().map(|(k, v)| (k.clone(), *v)).collect();
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        entries.truncate(count);
+        entries
+    }
+}
+
+fn reset_statistics(mut statistics: ResMut<RunStatistics>) {
+    *statistics = RunStatistics::default();
+}
+
+fn sample_due(clock: Res<GameClock>, statistics: Res<RunStatistics>) -> bool {
+    match statistics.samples.last() {
+        Some(sample) => clock.elapsed - sample.elapsed >= SAMPLE_INTERVAL_SECS,
+        None => true,
+    }
+}
+
+fn record_sample(
+    clock: Res<GameClock>,
+    ledger: Res<Ledger>,
+    mut statistics: ResMut<RunStatistics>,
+) {
+    statistics.samples.push(StatSample {
+        elapsed: clock.elapsed,
+        deliveries: ledger.fulfilled,
+        money: ledger.money,
+    });
+}
+
+fn satellite_name(name_query: &Query<&Name>, satellite: Entity) -> String {
+    name_query
+        .get(satellite)
+        .map_or_else(|_| "Unknown".to_string(), |name| name.to_string())
+}
+
+fn record_delivery(
+    _trigger: Trigger<OrderFulfilled>,
+    clock: Res<GameClock>,
+    mut statistics: ResMut<RunStatistics>,
+) {
+    statistics.current_cycle(&clock).deliveries += 1;
+}
+
+fn record_failure(
+    trigger: Trigger<OrderFailed>,
+    name_query: Query<&Name>,
+    clock: Res<GameClock>,
+    mut statistics: ResMut<RunStatistics>,
+) {
+    statistics.current_cycle(&clock).failures += 1;
+    let name = satellite_name(&name_query, trigger.event().satellite);
+    *statistics.failures_by_satellite.entry(name).or_default() += 1;
+}
+
+fn record_traffic(
+    trigger: Trigger<CargoHopped>,
+    name_query: Query<&Name>,
+    mut statistics: ResMut<RunStatistics>,
+) {
+    let mut ends = [
+        satellite_name(&name_query, trigger.event().from),
+        satellite_name(&name_query, trigger.event().to),
+    ];
+    // Traffic in either direction counts towards the same connection.
+    ends.sort();
+    let [a, b] = ends;
+    *statistics.connection_traffic.entry((a, b)).or_default() += 1;
+}
@@ -0,0 +1,372 @@
+//! Random events that interrupt a level for a while: solar flares, meteor showers,
+//! trade booms and production strikes. How often they happen is set per level,
+//! and the HUD lists the ones under way with a countdown.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_vector_shapes::{
+    painter::ShapePainter,
+    shapes::{DiscPainter, LinePainter},
+};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    assets::{HandleMap, LevelKey, SfxKey},
+    audio::sfx::PlaySfx,
+    campaign::CurrentLevel,
+    palette::GamePalette,
+    resource::{
+        DemandRules, DoResourceDemand, ProductionHalted, ResourceConsumer, ResourceSpawner,
+    },
+    rng::GameRng,
+    spawn::{
+        connection::{ConnectionAnchor, ConnectionTarget, ConnectionUnderConstruction},
+        level::{Level, Sun, SUN_RADIUS},
+        planet::{OrbitalPosition, SatelliteProperties},
+    },
+};
+use crate::{screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<SystemEvents>();
+
+    app.add_systems(OnEnter(Screen::Playing), reset_system_events);
+    app.observe(add_boom_demands);
+    app.add_systems(
+        Update,
+        (start_system_events, end_system_events)
+            .chain()
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+    app.add_systems(
+        Update,
+        draw_system_events
+            .in_set(AppSet::Render)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// How large the sun grows during a solar flare.
+const FLARE_SUN_RADIUS: f32 = 70.0;
+
+/// Extra orders a booming consumer places with every wave.
+const BOOM_EXTRA_DEMANDS: u32 = 2;
+
+/// How a level schedules random events, from its `events` entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventSchedule {
+    /// The shortest and longest wait between events, in seconds.
+    pub interval: (f32, f32),
+    /// The kinds of event that can happen.
+    #[serde(default = "SystemEventKind::all")]
+    pub kinds: Vec<SystemEventKind>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SystemEventKind {
+    /// The sun grows, breaking connections that pass too close to it.
+    SolarFlare,
+    /// A connection is cut once the countdown runs out.
+    MeteorShower,
+    /// A consumer places extra orders.
+    TradeBoom,
+    /// A spawner stops producing cargo.
+    Strike,
+}
+
+impl SystemEventKind {
+    fn all() -> Vec<Self> {
+        vec![
+            Self::SolarFlare,
+            Self::MeteorShower,
+            Self::TradeBoom,
+            Self::Strike,
+        ]
+    }
+
+    /// How long the event lasts, in seconds.
+    fn duration(&self) -> f32 {
+        match self {
+            SystemEventKind::SolarFlare => 15.0,
+            SystemEventKind::MeteorShower => 10.0,
+            SystemEventKind::TradeBoom => 25.0,
+            SystemEventKind::Strike => 20.0,
+        }
+    }
+}
+
+/// An event under way.
+#[derive(Debug)]
+pub struct ActiveEvent {
+    pub kind: SystemEventKind,
+    /// The satellite or connection the event is about, if any.
+    pub target: Option<Entity>,
+    /// What the notification feed calls the event.
+    pub label: String,
+    pub remaining: Timer,
+}
+
+impl ActiveEvent {
+    /// The countdown shown in the notification feed.
+    pub fn countdown(&self) -> String {
+        let seconds = self.remaining.remaining_secs().ceil();
+        match self.kind {
+            SystemEventKind::MeteorShower => format!("impact in {seconds:.0}s"),
+            _ => format!("{seconds:.0}s left"),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct SystemEvents {
+    pub active: Vec<ActiveEvent>,
+    /// Counts down to the next event.
+    next: Timer,
+}
+
+fn level_schedule<'a>(
+    current_level: &CurrentLevel,
+    level_handles: &HandleMap<LevelKey>,
+    levels: &'a Assets<Level>,
+) -> Option<&'a EventSchedule> {
+    levels
+        .get(&level_handles[&current_level.0])
+        .and_then(|level| level.events.as_ref())
+}
+
+fn reset_system_events(
+    current_level: Res<CurrentLevel>,
+    level_handles: Res<HandleMap<LevelKey>>,
+    levels: Res<Assets<Level>>,
+    mut events: ResMut<SystemEvents>,
+) {
+    // The first event waits as long as possible, so a level starts calm.
+    let wait = level_schedule(&current_level, &level_handles, &levels)
+        .map_or(0.0, |schedule| schedule.interval.1);
+    *events = SystemEvents {
+        active: Vec::new(),
+        next: Timer::from_seconds(wait, TimerMode::Once),
+    };
+}
+
+fn start_system_events(
+    mut commands: Commands,
+    time: Res<Time>,
+    current_level: Res<CurrentLevel>,
+    level_handles: Res<HandleMap<LevelKey>>,
+    levels: Res<Assets<Level>>,
+    mut events: ResMut<SystemEvents>,
+    mut sun: ResMut<Sun>,
+    mut game_rng: ResMut<GameRng>,
+    consumer_query: Query<(Entity, &Name), With<ResourceConsumer>>,
+    spawner_query: Query<(Entity, &Name), (With<ResourceSpawner>, Without<ProductionHalted>)>,
+    connection_query: Query<Entity, (With<ConnectionAnchor>, Without<ConnectionUnderConstruction>)>,
+) {
+    let Some(schedule) = level_schedule(&current_level, &level_handles, &levels) else {
+        return;
+    };
+
+    events.next.tick(time.delta());
+    if !events.next.finished() {
+        return;
+    }
+    let (min, max) = schedule.interval;
+    let wait = game_rng.rng.gen_range(min..=max.max(min));
+    events.next = Timer::from_seconds(wait, TimerMode::Once);
+
+    let Some(&kind) = schedule.kinds.choose(&mut game_rng.rng) else {
+        return;
+    };
+    let busy = |target: Entity| {
+        events
+            .active
+            .iter()
+            .any(|event| event.target == Some(target))
+    };
+    let (target, label) = match kind {
+        SystemEventKind::SolarFlare => {
+            if events.active.iter().any(|event| event.kind == kind) {
+                return;
+            }
+            sun.radius = FLARE_SUN_RADIUS;
+            (None, "Solar flare".to_string())
+        }
+        SystemEventKind::MeteorShower => {
+            let connections: Vec<_> = connection_query.iter().filter(|e| !busy(*e)).collect();
+            let Some(&connection) = connections.choose(&mut game_rng.rng) else {
+                return;
+            };
+            (Some(connection), "Meteor shower".to_string())
+        }
+        SystemEventKind::TradeBoom => {
+            let consumers: Vec<_> = consumer_query.iter().filter(|(e, _)| !busy(*e)).collect();
+            let Some(&(consumer, name)) = consumers.choose(&mut game_rng.rng) else {
+                return;
+            };
+            (Some(consumer), format!("Trade boom at {name}"))
+        }
+        SystemEventKind::Strike => {
+            let spawners: Vec<_> = spawner_query.iter().filter(|(e, _)| !busy(*e)).collect();
+            let Some(&(spawner, name)) = spawners.choose(&mut game_rng.rng) else {
+                return;
+            };
+            commands.entity(spawner).insert(ProductionHalted);
+            (Some(spawner), format!("Strike at {name}"))
+        }
+    };
+
+    events.active.push(ActiveEvent {
+        kind,
+        target,
+        label,
+        remaining: Timer::new(Duration::from_secs_f32(kind.duration()), TimerMode::Once),
+    });
+    commands.trigger(PlaySfx::Key(SfxKey::Alert));
+}
+
+fn end_system_events(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut events: ResMut<SystemEvents>,
+    mut sun: ResMut<Sun>,
+    connection_query: Query<(&ConnectionAnchor, &ConnectionTarget)>,
+    planet_query: Query<&OrbitalPosition>,
+) {
+    if events.active.is_empty() {
+        return;
+    }
+
+    for event in &mut events.active {
+        event.remaining.tick(time.delta());
+        if !event.remaining.just_finished() {
+            continue;
+        }
+
+        match (event.kind, event.target) {
+            (SystemEventKind::SolarFlare, _) => sun.radius = SUN_RADIUS,
+            (SystemEventKind::MeteorShower, Some(connection)) => {
+                // The connection may have been taken down before the meteors arrived.
+                let Ok((anchor, ConnectionTarget::Satellite(target))) =
+                    connection_query.get(connection)
+                else {
+                    continue;
+                };
+                if let (Ok(start), Ok(end)) = (
+                    planet_query.get(anchor.satellite),
+                    planet_query.get(*target),
+                ) {
+                    let midpoint = start
+                        .get_euclidean_position()
+                        .lerp(end.get_euclidean_position(), 0.5);
+                    commands.trigger(PlaySfx::At(SfxKey::LinkBreak, midpoint));
+                }
+                commands.entity(connection).despawn();
+            }
+            (SystemEventKind::Strike, Some(spawner)) => {
+                if let Some(mut spawner) = commands.get_entity(spawner) {
+                    spawner.remove::<ProductionHalted>();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    events.active.retain(|event| !event.remaining.finished());
+}
+
+fn add_boom_demands(
+    _trigger: Trigger<DoResourceDemand>,
+    events: Res<SystemEvents>,
+    rules: Res<DemandRules>,
+    mut game_rng: ResMut<GameRng>,
+    mut consumer_query: Query<&mut ResourceConsumer>,
+) {
+    for event in &events.active {
+        if event.kind != SystemEventKind::TradeBoom {
+            continue;
+        }
+        let Some(Ok(mut consumer)) = event.target.map(|e| consumer_query.get_mut(e)) else {
+            continue;
+        };
+        for _ in 0..BOOM_EXTRA_DEMANDS {
+            if let Some(&resource) = rules.resources.choose(&mut game_rng.rng) {
+                consumer.demands.push(resource);
+            }
+        }
+    }
+}
+
+fn draw_system_events(
+    mut painter: ShapePainter,
+    time: Res<Time<Real>>,
+    palette: Res<GamePalette>,
+    events: Res<SystemEvents>,
+    sun: Res<Sun>,
+    connection_query: Query<(&ConnectionAnchor, &ConnectionTarget)>,
+    satellite_query: Query<(&OrbitalPosition, &SatelliteProperties)>,
+) {
+    let t = time.elapsed_seconds();
+    let pulse = (t * 4.0).sin() * 0.5 + 0.5;
+
+    for event in &events.active {
+        match (event.kind, event.target) {
+            (SystemEventKind::SolarFlare, _) => {
+                painter.hollow = true;
+                painter.thickness = 2.0;
+                painter.set_color(Color::srgba(1.0, 0.6, 0.1, 0.3 + 0.4 * pulse));
+                painter.circle(sun.radius + 6.0 + 6.0 * pulse);
+            }
+            (SystemEventKind::MeteorShower, Some(connection)) => {
+                let Ok((anchor, ConnectionTarget::Satellite(target))) =
+                    connection_query.get(connection)
+                else {
+                    continue;
+                };
+                let (Ok((start, _)), Ok((end, _))) = (
+                    satellite_query.get(anchor.satellite),
+                    satellite_query.get(*target),
+                ) else {
+                    continue;
+                };
+                let (start, end) = (start.get_euclidean_position(), end.get_euclidean_position());
+
+                // Streaks fall towards the connection from the top right, faster as impact nears.
+                let speed = 1.0 + 2.0 * event.remaining.fraction();
+                painter.thickness = 1.0;
+                painter.set_color(palette.out_of_range);
+                for i in 0..5 {
+                    let phase = (t * speed + i as f32 / 5.0).fract();
+                    let landing = start.lerp(end, (i as f32 + 0.5) / 5.0);
+                    let head = landing + Vec3::new(1.0, 1.0, 0.0) * 120.0 * (1.0 - phase);
+                    painter.line(head, head + Vec3::new(12.0, 12.0, 0.0));
+                }
+            }
+            (SystemEventKind::TradeBoom, Some(consumer)) => {
+                let Ok((orbit, properties)) = satellite_query.get(consumer) else {
+                    continue;
+                };
+                painter.hollow = true;
+                painter.thickness = 1.5;
+                painter.set_color(palette.demand);
+                painter.set_translation(orbit.get_euclidean_position());
+                painter.circle(properties.radius + 6.0 + 8.0 * pulse);
+            }
+            (SystemEventKind::Strike, Some(spawner)) => {
+                let Ok((orbit, properties)) = satellite_query.get(spawner) else {
+                    continue;
+                };
+                let size = properties.radius + 4.0;
+                painter.thickness = 2.0;
+                painter.set_color(palette.out_of_range);
+                painter.set_translation(orbit.get_euclidean_position());
+                painter.line(Vec3::new(-size, -size, 0.0), Vec3::new(size, size, 0.0));
+                painter.line(Vec3::new(-size, size, 0.0), Vec3::new(size, -size, 0.0));
+            }
+            _ => (),
+        }
+        painter.set_translation(Vec3::ZERO);
+    }
+}
@@ -0,0 +1,295 @@
+//! Scripted tutorial steps. A level can list steps that show a message, point out satellites
+//! and wait for the player to do something. While a step waits, only the action it asks for is allowed.
+//! The callout that shows each step lives in `screen::tutorial`.
+
+use bevy::prelude::*;
+use bevy_vector_shapes::{
+    painter::ShapePainter,
+    shapes::{DiscPainter, LinePainter},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    assets::{HandleMap, LevelKey},
+    campaign::CurrentLevel,
+    interaction::InteractionGate,
+    ledger::Ledger,
+    palette::GamePalette,
+    spawn::{
+        connection::{ConnectionAnchor, ConnectionTarget, ConnectionUnderConstruction},
+        level::Level,
+        planet::{OrbitalPosition, SatelliteIndex, SatelliteProperties},
+    },
+};
+use crate::{screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Tutorial>();
+
+    app.add_systems(OnEnter(Screen::Playing), start_tutorial);
+    app.add_systems(OnExit(Screen::Playing), open_interaction_gate);
+    app.observe(continue_tutorial);
+    app.observe(skip_tutorial);
+    app.add_systems(
+        Update,
+        (check_tutorial_step, update_interaction_gate)
+            .chain()
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing).and_then(tutorial_active)),
+    );
+    app.add_systems(
+        Update,
+        draw_tutorial_highlights
+            .in_set(AppSet::Render)
+            .run_if(in_state(Screen::Playing).and_then(tutorial_active)),
+    );
+}
+
+/// How far outside a highlighted satellite its ring is drawn.
+const HIGHLIGHT_MARGIN: f32 = 12.0;
+
+/// One step of a level's tutorial.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TutorialStep {
+    pub message: String,
+    pub action: TutorialAction,
+}
+
+/// What a tutorial step waits for. Satellites are referred to by their position in the level file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TutorialAction {
+    /// Wait for the player to press Continue.
+    Read,
+    /// Point out a satellite, then wait for Continue.
+    Highlight(usize),
+    /// Wait for a connection between two satellites. Only those two can be clicked.
+    Connect(usize, usize),
+    /// Wait for every connection to be removed. Only connections can be clicked.
+    Disconnect,
+    /// Wait for the first delivery. Everything can be clicked.
+    Deliver,
+}
+
+impl TutorialAction {
+    /// Whether the step is finished by pressing Continue rather than by playing.
+    pub fn needs_continue(&self) -> bool {
+        matches!(self, TutorialAction::Read | TutorialAction::Highlight(_))
+    }
+
+    fn highlighted(&self) -> Vec<usize> {
+        match *self {
+            TutorialAction::Highlight(satellite) => vec![satellite],
+            TutorialAction::Connect(from, to) => vec![from, to],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// The current level's tutorial and how far the player is through it.
+#[derive(Resource, Debug, Default)]
+pub struct Tutorial {
+    pub steps: Vec<TutorialStep>,
+    pub current: usize,
+}
+
+impl Tutorial {
+    pub fn step(&self) -> Option<&TutorialStep> {
+        self.steps.get(self.current)
+    }
+}
+
+/// Finish a step that waits for Continue.
+#[derive(Event, Debug)]
+pub struct ContinueTutorial;
+
+/// Drop the rest of the tutorial and let the player do anything.
+#[derive(Event, Debug)]
+pub struct SkipTutorial;
+
+pub fn tutorial_active(tutorial: Res<Tutorial>) -> bool {
+    tutorial.step().is_some()
+}
+
+fn start_tutorial(
+    current_level: Res<CurrentLevel>,
+    level_handles: Res<HandleMap<LevelKey>>,
+    levels: Res<Assets<Level>>,
+    mut tutorial: ResMut<Tutorial>,
+    mut gate: ResMut<InteractionGate>,
+) {
+    *tutorial = Tutorial {
+        steps: levels
+            .get(&level_handles[&current_level.0])
+            .map(|level| level.tutorial.clone())
+            .unwrap_or_default(),
+        current: 0,
+    };
+    *gate = InteractionGate::default();
+}
+
+fn open_interaction_gate(mut gate: ResMut<InteractionGate>) {
+    *gate = InteractionGate::default();
+}
+
+fn continue_tutorial(_trigger: Trigger<ContinueTutorial>, mut tutorial: ResMut<Tutorial>) {
+    if tutorial
+        .step()
+        .is_some_and(|step| step.action.needs_continue())
+    {
+        tutorial.current += 1;
+    }
+}
+
+fn skip_tutorial(
+    _trigger: Trigger<SkipTutorial>,
+    mut tutorial: ResMut<Tutorial>,
+    mut gate: ResMut<InteractionGate>,
+) {
+    tutorial.current = tutorial.steps.len();
+    *gate = InteractionGate::default();
+}
+
+fn satellite_entity(
+    satellite_query: &Query<(
+        Entity,
+        &SatelliteIndex,
+        &OrbitalPosition,
+        &SatelliteProperties,
+    )>,
+    index: usize,
+) -> Option<Entity> {
+    satellite_query
+        .iter()
+        .find(|(_, satellite_index, ..)| satellite_index.0 == index)
+        .map(|(entity, ..)| entity)
+}
+
+fn check_tutorial_step(
+    mut tutorial: ResMut<Tutorial>,
+    ledger: Res<Ledger>,
+    satellite_query: Query<(
+        Entity,
+        &SatelliteIndex,
+        &OrbitalPosition,
+        &SatelliteProperties,
+    )>,
+    connection_query: Query<
+        (&ConnectionAnchor, &ConnectionTarget),
+        Without<ConnectionUnderConstruction>,
+    >,
+) {
+    let Some(step) = tutorial.step() else {
+        return;
+    };
+
+    let done = match step.action {
+        TutorialAction::Read | TutorialAction::Highlight(_) => false,
+        TutorialAction::Connect(from, to) => {
+            let ends = (
+                satellite_entity(&satellite_query, from),
+                satellite_entity(&satellite_query, to),
+            );
+            let (Some(from), Some(to)) = ends else {
+                return;
+            };
+            connection_query.iter().any(|(anchor, target)| {
+                let ConnectionTarget::Satellite(target) = target else {
+                    return false;
+                };
+                (anchor.satellite, *target) == (from, to)
+                    || (anchor.satellite, *target) == (to, from)
+            })
+        }
+        TutorialAction::Disconnect => connection_query.is_empty(),
+        TutorialAction::Deliver => ledger.fulfilled > 0,
+    };
+    if done {
+        tutorial.current += 1;
+    }
+}
+
+fn update_interaction_gate(
+    tutorial: Res<Tutorial>,
+    satellite_query: Query<(
+        Entity,
+        &SatelliteIndex,
+        &OrbitalPosition,
+        &SatelliteProperties,
+    )>,
+    mut gate: ResMut<InteractionGate>,
+) {
+    let Some(step) = tutorial.step() else {
+        return;
+    };
+
+    let allowed = match step.action {
+        TutorialAction::Read | TutorialAction::Highlight(_) => InteractionGate {
+            satellites: Some(Vec::new()),
+            remove_connections: false,
+        },
+        TutorialAction::Connect(from, to) => InteractionGate {
+            satellites: Some(
+                [from, to]
+                    .into_iter()
+                    .filter_map(|index| satellite_entity(&satellite_query, index))
+                    .collect(),
+            ),
+            remove_connections: false,
+        },
+        TutorialAction::Disconnect => InteractionGate {
+            satellites: Some(Vec::new()),
+            remove_connections: true,
+        },
+        TutorialAction::Deliver => InteractionGate::default(),
+    };
+    if *gate != allowed {
+        *gate = allowed;
+    }
+}
+
+fn draw_tutorial_highlights(
+    mut painter: ShapePainter,
+    time: Res<Time<Real>>,
+    palette: Res<GamePalette>,
+    tutorial: Res<Tutorial>,
+    satellite_query: Query<(
+        Entity,
+        &SatelliteIndex,
+        &OrbitalPosition,
+        &SatelliteProperties,
+    )>,
+) {
+    let Some(step) = tutorial.step() else {
+        return;
+    };
+
+    let positions: Vec<_> = step
+        .action
+        .highlighted()
+        .into_iter()
+        .filter_map(|index| {
+            satellite_query
+                .iter()
+                .find(|(_, satellite_index, ..)| satellite_index.0 == index)
+                .map(|(_, _, orbit, properties)| {
+                    (orbit.get_euclidean_position(), properties.radius)
+                })
+        })
+        .collect();
+
+    // Pulse so the highlight stands out even while the game is paused.
+    let pulse = (time.elapsed_seconds() * 4.0).sin() * 3.0;
+    painter.hollow = true;
+    painter.thickness = 2.0;
+    painter.set_color(palette.range_guide);
+    for (position, radius) in &positions {
+        painter.set_translation(*position);
+        painter.circle(radius + HIGHLIGHT_MARGIN + pulse);
+    }
+    painter.set_translation(Vec3::ZERO);
+
+    if let [(from, _), (to, _)] = positions[..] {
+        painter.thickness = 1.0;
+        painter.line(from, to);
+    }
+}
@@ -1,17 +1,17 @@
+mod calendar;
+mod clipboard;
 #[cfg(feature = "dev")]
 mod dev_tools;
 mod game;
+mod persistence;
 mod screen;
+mod settings;
 mod ui;
 
-use bevy::{
-    asset::AssetMetaCheck,
-    audio::{AudioPlugin, Volume},
-    core_pipeline::bloom::BloomSettings,
-    prelude::*,
-    window::WindowResolution,
-};
+use bevy::{asset::AssetMetaCheck, audio::AudioPlugin, prelude::*};
 use bevy_vector_shapes::Shape2dPlugin;
+use game::audio::sfx::LISTENER_EAR_GAP;
+use settings::Settings;
 
 pub struct AppPlugin;
 
@@ -26,6 +26,9 @@ impl Plugin for AppPlugin {
         // Spawn the main camera.
         app.add_systems(Startup, spawn_camera);
 
+        // Load the player's settings first so the plugins below start out configured.
+        let settings = Settings::load();
+
         // Add Bevy plugins.
         app.add_plugins(
             DefaultPlugins
@@ -42,22 +45,22 @@ impl Plugin for AppPlugin {
                         canvas: Some("#bevy".to_string()),
                         fit_canvas_to_parent: true,
                         prevent_default_event_handling: true,
-                        resolution: WindowResolution::new(720.0, 720.0),
+                        resolution: settings.window_resolution(),
+                        mode: settings.window_mode(),
                         ..default()
                     }
                     .into(),
                     ..default()
                 })
                 .set(AudioPlugin {
-                    global_volume: GlobalVolume {
-                        volume: Volume::new(0.3),
-                    },
+                    global_volume: settings.global_volume(),
                     ..default()
                 }),
         );
+        app.insert_resource(settings);
 
         // Add other plugins.
-        app.add_plugins((game::plugin, screen::plugin, ui::plugin));
+        app.add_plugins((game::plugin, screen::plugin, ui::plugin, settings::plugin));
         app.add_plugins(Shape2dPlugin::default());
 
         // Enable dev tools for dev builds.
@@ -93,7 +96,6 @@ fn spawn_camera(mut commands: Commands) {
             },
             ..Default::default()
         },
-        BloomSettings::default(),
         // Render all UI to this camera.
         // Not strictly necessary since we only use one camera,
         // but if we don't use this component, our UI will disappear as soon
@@ -101,5 +103,7 @@ fn spawn_camera(mut commands: Commands) {
         // [ui node outlines](https://bevyengine.org/news/bevy-0-14/#ui-node-outline-gizmos)
         // for debugging. So it's good to have this here for future-proofing.
         IsDefaultUiCamera,
+        // Positional sound effects are heard relative to the camera.
+        SpatialListener::new(LISTENER_EAR_GAP),
     ));
 }
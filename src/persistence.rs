@@ -0,0 +1,63 @@
+//! Save and load small pieces of data between sessions.
+//! Native builds write a RON file per key into the `saves` directory,
+//! web builds keep the same RON text in the browser's local storage.
+
+use bevy::log::warn;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Load the value stored under `key`, if there is one and it can be parsed.
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let text = read(key)?;
+    match ron::from_str(&text) {
+        Ok(value) => Some(value),
+        Err(error) => {
+            warn!("Could not parse saved data \"{key}\": {error}");
+            None
+        }
+    }
+}
+
+/// Store `value` under `key`, replacing anything saved there before.
+pub fn save<T: Serialize>(key: &str, value: &T) {
+    match ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default()) {
+        Ok(text) => write(key, &text),
+        Err(error) => warn!("Could not serialize data \"{key}\": {error}"),
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+const SAVE_DIRECTORY: &str = "saves";
+
+#[cfg(not(target_family = "wasm"))]
+fn read(key: &str) -> Option<String> {
+    let path = std::path::Path::new(SAVE_DIRECTORY).join(format!("{key}.ron"));
+    std::fs::read_to_string(path).ok()
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn write(key: &str, text: &str) {
+    let directory = std::path::Path::new(SAVE_DIRECTORY);
+    let result = std::fs::create_dir_all(directory)
+        .and_then(|_| std::fs::write(directory.join(format!("{key}.ron")), text));
+    if let Err(error) = result {
+        warn!("Could not write saved data \"{key}\": {error}");
+    }
+}
+
+#[cfg(target_family = "wasm")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_family = "wasm")]
+fn read(key: &str) -> Option<String> {
+    local_storage()?.get_item(key).ok()?
+}
+
+#[cfg(target_family = "wasm")]
+fn write(key: &str, text: &str) {
+    let stored = local_storage().and_then(|storage| storage.set_item(key, text).ok());
+    if stored.is_none() {
+        warn!("Could not write saved data \"{key}\" to local storage");
+    }
+}
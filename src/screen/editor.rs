@@ -0,0 +1,361 @@
+//! The sandbox editor's panel: tool selection, an inspector for the selected satellite and export.
+
+use bevy::{prelude::*, ui::RelativeCursorPosition};
+
+use super::Screen;
+use crate::{
+    game::{
+        editor::{
+            in_sandbox, role_of, DeleteSatellite, Editor, EditorTool, ExportLevel, LastExport,
+            MAX_ORBIT_RADIUS, MIN_ORBIT_RADIUS,
+        },
+        market::MarketPrices,
+        resource::{
+            GameResource, ResourceConsumer, ResourceContainer, ResourceSpawner, Warehouse,
+            WAREHOUSE_STORAGE_SIZE,
+        },
+        spawn::planet::{
+            apply_role, OrbitalMovement, OrbitalPosition, SatelliteProperties, SatelliteRole,
+        },
+    },
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(EditorAction, EditorControl, InspectorBody)>();
+    app.add_systems(
+        OnEnter(Screen::Playing),
+        spawn_editor_panel.run_if(in_sandbox),
+    );
+    app.add_systems(
+        Update,
+        (
+            handle_editor_action,
+            select_tool,
+            rebuild_inspector,
+            sync_inspector_sliders,
+            apply_role_dropdown,
+            update_editor_readouts,
+        )
+            .chain()
+            .run_if(in_state(Screen::Playing).and_then(in_sandbox)),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum EditorAction {
+    Delete,
+    Export,
+}
+
+/// Which value a control or readout of the editor panel shows.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum EditorControl {
+    Tool,
+    OrbitRadius,
+    OrbitSpeed,
+    Size,
+    Storage,
+    Role,
+    /// The market price of `GameResource::ALL[index]` at the selected consumer.
+    Price(usize),
+    Export,
+}
+
+/// The part of the panel that is rebuilt for the selected satellite.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct InspectorBody(Option<Entity>);
+
+const TOOLS: [(EditorTool, &str); 3] = [
+    (EditorTool::Connect, "Connect"),
+    (EditorTool::Move, "Move"),
+    (EditorTool::Place, "Place"),
+];
+
+/// Every role a satellite can be switched to, in dropdown order.
+fn role_options() -> Vec<SatelliteRole> {
+    let mut roles = vec![
+        SatelliteRole::Relay,
+        SatelliteRole::Warehouse,
+        SatelliteRole::Consumer(Vec::new()),
+    ];
+    roles.extend(GameResource::ALL.map(SatelliteRole::Spawner));
+    roles
+}
+
+fn role_label(role: &SatelliteRole) -> String {
+    match role {
+        SatelliteRole::Relay => "Relay".to_string(),
+        SatelliteRole::Warehouse => "Warehouse".to_string(),
+        SatelliteRole::Consumer(_) => "Consumer".to_string(),
+        SatelliteRole::Spawner(resource) => format!("Spawner ({resource:?})"),
+    }
+}
+
+fn spawn_editor_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Editor Root"),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(8.0),
+                    top: Val::Px(64.0),
+                    ..default()
+                },
+                ..default()
+            },
+            StateScoped(Screen::Playing),
+        ))
+        .with_children(|children| {
+            // Lets the editor tell clicks on the panel apart from clicks on the board.
+            children
+                .panel("Editor")
+                .insert(RelativeCursorPosition::default())
+                .with_children(|children| {
+                    children
+                        .dropdown(TOOLS.map(|(_, name)| name.to_string()).to_vec(), 0)
+                        .insert(EditorControl::Tool);
+                    children.spawn((
+                        Name::new("Inspector"),
+                        InspectorBody(None),
+                        NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::Center,
+                                row_gap: Val::Px(6.0),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                    ));
+                    children.small_button("Export").insert(EditorAction::Export);
+                    children.readout("Export").insert(EditorControl::Export);
+                });
+        });
+}
+
+fn handle_editor_action(
+    mut commands: Commands,
+    editor: Res<Editor>,
+    mut button_query: InteractionQuery<&EditorAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                EditorAction::Delete => {
+                    if let Some(satellite) = editor.selected {
+                        commands.trigger(DeleteSatellite(satellite));
+                    }
+                }
+                EditorAction::Export => commands.trigger(ExportLevel),
+            }
+        }
+    }
+}
+
+fn select_tool(
+    mut editor: ResMut<Editor>,
+    dropdown_query: Query<(&Dropdown, &EditorControl), Changed<Dropdown>>,
+) {
+    for (dropdown, control) in &dropdown_query {
+        if *control == EditorControl::Tool {
+            if let Some((tool, _)) = TOOLS.get(dropdown.selected) {
+                editor.tool = *tool;
+            }
+        }
+    }
+}
+
+fn rebuild_inspector(
+    mut commands: Commands,
+    editor: Res<Editor>,
+    mut body_query: Query<(Entity, &mut InspectorBody)>,
+    satellite_query: Query<(
+        &Name,
+        &OrbitalPosition,
+        &OrbitalMovement,
+        &SatelliteProperties,
+        &ResourceContainer,
+        Option<&ResourceSpawner>,
+        Option<&ResourceConsumer>,
+        Has<Warehouse>,
+    )>,
+) {
+    for (entity, mut body) in &mut body_query {
+        if body.0 == editor.selected && !body.is_added() {
+            continue;
+        }
+        body.0 = editor.selected;
+
+        let mut body = commands.entity(entity);
+        body.despawn_descendants();
+        let Some(Ok((name, orbit, movement, properties, container, spawner, consumer, warehouse))) =
+            editor.selected.map(|e| satellite_query.get(e))
+        else {
+            body.with_children(|children| {
+                children.readout("Select a satellite with Move or Place");
+            });
+            continue;
+        };
+
+        let roles = role_options();
+        let role = role_label(&role_of(spawner, consumer, warehouse));
+        body.with_children(|children| {
+            children.header(name.to_string());
+            children
+                .readout("Orbit radius")
+                .insert(EditorControl::OrbitRadius);
+            children
+                .slider(orbit.radius, MIN_ORBIT_RADIUS, MAX_ORBIT_RADIUS)
+                .insert(EditorControl::OrbitRadius);
+            children
+                .readout("Orbit speed")
+                .insert(EditorControl::OrbitSpeed);
+            children
+                .slider(movement.speed, 0.0, 0.3)
+                .insert(EditorControl::OrbitSpeed);
+            children.readout("Size").insert(EditorControl::Size);
+            children
+                .slider(properties.radius, 2.0, 24.0)
+                .insert(EditorControl::Size);
+            children.readout("Storage").insert(EditorControl::Storage);
+            children
+                .slider(
+                    container.storage_size as f32,
+                    1.0,
+                    WAREHOUSE_STORAGE_SIZE as f32,
+                )
+                .insert(EditorControl::Storage);
+            children
+                .dropdown(
+                    roles.iter().map(role_label).collect(),
+                    roles
+                        .iter()
+                        .position(|option| role_label(option) == role)
+                        .unwrap_or_default(),
+                )
+                .insert(EditorControl::Role);
+            if consumer.is_some() {
+                for (index, resource) in GameResource::ALL.iter().enumerate() {
+                    children
+                        .readout(format!("{resource:?} price"))
+                        .insert(EditorControl::Price(index));
+                }
+            }
+            children.small_button("Delete").insert(EditorAction::Delete);
+        });
+    }
+}
+
+/// Sliders being dragged edit the selected satellite, the others follow it,
+/// so they stay in step when it is dragged around the board.
+fn sync_inspector_sliders(
+    editor: Res<Editor>,
+    mut slider_query: Query<(&EditorControl, &Interaction, &mut Slider)>,
+    mut satellite_query: Query<(
+        &mut OrbitalPosition,
+        &mut OrbitalMovement,
+        &mut SatelliteProperties,
+        &mut ResourceContainer,
+    )>,
+) {
+    let Some(Ok((mut orbit, mut movement, mut properties, mut container))) =
+        editor.selected.map(|e| satellite_query.get_mut(e))
+    else {
+        return;
+    };
+
+    for (control, interaction, mut slider) in &mut slider_query {
+        if *interaction == Interaction::Pressed {
+            match control {
+                EditorControl::OrbitRadius => orbit.radius = slider.value,
+                EditorControl::OrbitSpeed => movement.speed = slider.value,
+                EditorControl::Size => properties.radius = slider.value,
+                EditorControl::Storage => container.storage_size = slider.value.round() as usize,
+                _ => (),
+            }
+        } else {
+            let value = match control {
+                EditorControl::OrbitRadius => orbit.radius,
+                EditorControl::OrbitSpeed => movement.speed,
+                EditorControl::Size => properties.radius,
+                EditorControl::Storage => container.storage_size as f32,
+                _ => continue,
+            };
+            if slider.value != value {
+                slider.value = value;
+            }
+        }
+    }
+}
+
+fn apply_role_dropdown(
+    mut commands: Commands,
+    editor: Res<Editor>,
+    dropdown_query: Query<(&Dropdown, &EditorControl), Changed<Dropdown>>,
+    role_query: Query<(
+        Option<&ResourceSpawner>,
+        Option<&ResourceConsumer>,
+        Has<Warehouse>,
+    )>,
+) {
+    let Some(satellite) = editor.selected else {
+        return;
+    };
+    let Ok((spawner, consumer, warehouse)) = role_query.get(satellite) else {
+        return;
+    };
+
+    for (dropdown, control) in &dropdown_query {
+        if *control != EditorControl::Role {
+            continue;
+        }
+        let Some(role) = role_options().into_iter().nth(dropdown.selected) else {
+            continue;
+        };
+        if role_label(&role) != role_label(&role_of(spawner, consumer, warehouse)) {
+            apply_role(&mut commands.entity(satellite), &role);
+        }
+    }
+}
+
+fn update_editor_readouts(
+    editor: Res<Editor>,
+    last_export: Res<LastExport>,
+    satellite_query: Query<(
+        &OrbitalPosition,
+        &OrbitalMovement,
+        &SatelliteProperties,
+        &ResourceContainer,
+        Option<&MarketPrices>,
+    )>,
+    mut readout_query: Query<(&EditorControl, &mut Text)>,
+) {
+    let selected = editor.selected.and_then(|e| satellite_query.get(e).ok());
+    for (control, mut text) in &mut readout_query {
+        let value = match (control, selected) {
+            (EditorControl::OrbitRadius, Some((orbit, ..))) => format!("{:.0}", orbit.radius),
+            (EditorControl::OrbitSpeed, Some((_, movement, ..))) => {
+                format!("{:.3}", movement.speed)
+            }
+            (EditorControl::Size, Some((_, _, properties, ..))) => {
+                format!("{:.1}", properties.radius)
+            }
+            (EditorControl::Storage, Some((.., container, _))) => {
+                container.storage_size.to_string()
+            }
+            (EditorControl::Price(index), Some((.., Some(market)))) => {
+                format!("${}", market.price(GameResource::ALL[*index]))
+            }
+            (EditorControl::Export, _) => last_export.0.clone().unwrap_or_default(),
+            _ => continue,
+        };
+        if text.sections[1].value != value {
+            text.sections[1].value = value;
+        }
+    }
+}
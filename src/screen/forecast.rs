@@ -0,0 +1,129 @@
+//! The forecast overlay, listing the alignments under way and the ones coming up.
+
+use bevy::prelude::*;
+
+use super::Screen;
+use crate::{
+    game::{
+        alignment::{level_bonuses, Alignments, Forecast},
+        assets::{HandleMap, LevelKey},
+        campaign::CurrentLevel,
+        spawn::level::Level,
+    },
+    ui::prelude::*,
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<ForecastText>();
+    app.add_systems(
+        Update,
+        (show_forecast, update_forecast)
+            .chain()
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// The text of the forecast panel.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct ForecastText;
+
+/// The root of the forecast panel.
+#[derive(Component, Debug)]
+struct ForecastRoot;
+
+fn show_forecast(
+    mut commands: Commands,
+    forecast: Res<Forecast>,
+    root_query: Query<Entity, With<ForecastRoot>>,
+) {
+    match (forecast.visible, root_query.get_single()) {
+        (true, Err(_)) => {
+            commands
+                .spawn((
+                    Name::new("Forecast Root"),
+                    ForecastRoot,
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            left: Val::Px(8.0),
+                            bottom: Val::Px(8.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    StateScoped(Screen::Playing),
+                ))
+                .with_children(|children| {
+                    children.panel("Forecast").with_children(|children| {
+                        children.label("").insert(ForecastText);
+                    });
+                });
+        }
+        (false, Ok(root)) => commands.entity(root).despawn_recursive(),
+        _ => (),
+    }
+}
+
+fn update_forecast(
+    forecast: Res<Forecast>,
+    alignments: Res<Alignments>,
+    current_level: Res<CurrentLevel>,
+    level_handles: Res<HandleMap<LevelKey>>,
+    levels: Res<Assets<Level>>,
+    name_query: Query<&Name>,
+    mut text_query: Query<&mut Text, With<ForecastText>>,
+) {
+    let level = levels.get(&level_handles[&current_level.0]);
+    let name = |entity: Entity| {
+        name_query
+            .get(entity)
+            .map_or_else(|_| "?".to_string(), |name| name.to_string())
+    };
+
+    let mut lines = Vec::new();
+    for alignment in &alignments.0 {
+        let satellites: Vec<_> = alignment.satellites.iter().map(|e| name(*e)).collect();
+        let bonuses: Vec<_> = level_bonuses(level, alignment.kind)
+            .map(|bonus| bonus.label())
+            .collect();
+        let mut line = format!(
+            "Now: {} of {}",
+            alignment.kind.label(),
+            satellites.join(", ")
+        );
+        if !bonuses.is_empty() {
+            line += &format!(" ({})", bonuses.join(", "));
+        }
+        lines.push(line);
+    }
+    if let Some(alignment) = &forecast.last_ended {
+        let satellites: Vec<_> = alignment.satellites.iter().map(|e| name(*e)).collect();
+        lines.push(format!(
+            "Ended: {} of {}",
+            alignment.kind.label(),
+            satellites.join(", ")
+        ));
+    }
+    for entry in &forecast.upcoming {
+        lines.push(format!(
+            "{} of {} and {} in {:.0}s",
+            entry.kind.label(),
+            name(entry.satellites.0),
+            name(entry.satellites.1),
+            entry.seconds.ceil(),
+        ));
+    }
+    if forecast.upcoming.is_empty() {
+        lines.push("Nothing lines up in the next minute".to_string());
+    }
+
+    let value = lines.join("\n");
+    for mut text in &mut text_query {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
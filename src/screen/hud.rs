@@ -0,0 +1,306 @@
+//! The heads-up display shown on top of the playing screen.
+
+use bevy::prelude::*;
+
+use super::{Menu, Screen};
+use crate::{
+    game::{
+        assets::{HandleMap, LevelKey},
+        campaign::CurrentLevel,
+        clock::{GameClock, TimeScale},
+        interaction::InteractionState,
+        ledger::Ledger,
+        market::MarketPrices,
+        objective::{Objective, ObjectiveProgress},
+        research::{CycleLinkKind, SelectedLink},
+        resource::{GameResource, GameResourceDemand, PinnedConsumer},
+        spawn::{
+            connection::{ConnectionAnchor, ConnectionUnderConstruction},
+            level::Level,
+        },
+        station::{ToggleStationBuilder, STATION_COST},
+        system_event::SystemEvents,
+    },
+    ui::prelude::*,
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(HudStat, HudObjective, HudEventFeed, HudEventRow, HudAction)>();
+    app.add_systems(OnEnter(Screen::Playing), spawn_hud);
+    app.add_systems(
+        Update,
+        (
+            handle_hud_action,
+            update_hud,
+            update_link_button,
+            update_hud_objectives,
+            update_event_feed,
+        )
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// Which statistic a HUD readout displays.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum HudStat {
+    Cycle,
+    Money,
+    Score,
+    Orders,
+    Fleet,
+    TimeScale,
+    /// What the consumer under the mouse pays, or else a pinned one.
+    Prices,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum HudAction {
+    BuildStation,
+    Research,
+    CycleLink,
+}
+
+/// A HUD readout for the level objective at this index.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct HudObjective(usize);
+
+/// Lists the random events under way. Holds the labels of the rows it shows,
+/// so an event replaced by another in the same frame still updates the list.
+#[derive(Component, Debug, Clone, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct HudEventFeed(Vec<String>);
+
+/// A row of the event feed, for the active event at this index.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct HudEventRow(usize);
+
+fn spawn_hud(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    level_handles: Res<HandleMap<LevelKey>>,
+    levels: Res<Assets<Level>>,
+) {
+    let objectives = levels
+        .get(&level_handles[&current_level.0])
+        .map(|level| level.objectives.clone())
+        .unwrap_or_default();
+
+    commands
+        .hud_root()
+        .insert(StateScoped(Screen::Playing))
+        .with_children(|children| {
+            children.toolbar().with_children(|children| {
+                children.readout("Cycle").insert(HudStat::Cycle);
+                children.readout("Money").insert(HudStat::Money);
+                children.readout("Score").insert(HudStat::Score);
+                children.readout("Orders").insert(HudStat::Orders);
+                children.readout("Ships").insert(HudStat::Fleet);
+                children.readout("Speed").insert(HudStat::TimeScale);
+            });
+
+            children.toolbar().with_children(|children| {
+                children
+                    .small_button(format!("Build Relay (${STATION_COST})"))
+                    .insert((
+                        HudAction::BuildStation,
+                        Tooltip::new("Needs the Relay Stations research."),
+                    ));
+                children
+                    .small_button("Research")
+                    .insert(HudAction::Research);
+                children.small_button("Link: Standard").insert((
+                    HudAction::CycleLink,
+                    Tooltip::new("The kind of link new connections are built as."),
+                ));
+            });
+
+            children.toolbar().with_children(|children| {
+                children.readout("Prices").insert(HudStat::Prices);
+            });
+
+            if !objectives.is_empty() {
+                children.toolbar().with_children(|children| {
+                    for (index, objective) in objectives.iter().enumerate() {
+                        children
+                            .readout(objective.label())
+                            .insert(HudObjective(index));
+                    }
+                });
+            }
+
+            children.spawn((
+                Name::new("Event Feed"),
+                HudEventFeed::default(),
+                NodeBundle {
+                    style: Style {
+                        align_self: AlignSelf::FlexStart,
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::axes(Val::Px(16.0), Val::Px(8.0)),
+                        row_gap: Val::Px(4.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn handle_hud_action(
+    mut commands: Commands,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut button_query: InteractionQuery<&HudAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                HudAction::BuildStation => commands.trigger(ToggleStationBuilder),
+                HudAction::Research => next_menu.set(Menu::Research),
+                HudAction::CycleLink => commands.trigger(CycleLinkKind),
+            }
+        }
+    }
+}
+
+fn update_link_button(
+    selected_link: Res<SelectedLink>,
+    button_query: Query<(&HudAction, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !selected_link.is_changed() {
+        return;
+    }
+
+    let value = format!("Link: {}", selected_link.0.label());
+    for (action, children) in &button_query {
+        if *action != HudAction::CycleLink {
+            continue;
+        }
+        for child in children {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value.clone_from(&value);
+            }
+        }
+    }
+}
+
+fn update_hud(
+    clock: Res<GameClock>,
+    time_scale: Res<TimeScale>,
+    ledger: Res<Ledger>,
+    demand_query: Query<(), With<GameResourceDemand>>,
+    connection_query: Query<(), (With<ConnectionAnchor>, Without<ConnectionUnderConstruction>)>,
+    market_query: Query<(&Name, &MarketPrices, &InteractionState, Has<PinnedConsumer>)>,
+    mut hud_query: Query<(&HudStat, &mut Text)>,
+) {
+    let market = market_query
+        .iter()
+        .find(|(.., interaction, _)| **interaction != InteractionState::None)
+        .or_else(|| market_query.iter().find(|(.., pinned)| *pinned));
+
+    for (stat, mut text) in &mut hud_query {
+        let value = match stat {
+            HudStat::Cycle => format!(
+                "{} ({:.0}%)",
+                clock.cycle() + 1,
+                clock.cycle_progress() * 100.0
+            ),
+            HudStat::Money => format!("${}", ledger.money),
+            HudStat::Orders => format!(
+                "{} open / {} done / {} failed",
+                demand_query.iter().count(),
+                ledger.fulfilled,
+                ledger.failed
+            ),
+            HudStat::Score => ledger.score.to_string(),
+            // Every established connection is flown by one ship.
+            HudStat::Fleet => format!("{} in use", connection_query.iter().count()),
+            HudStat::TimeScale => {
+                if time_scale.paused {
+                    "Paused".to_string()
+                } else {
+                    format!("x{}", time_scale.speed)
+                }
+            }
+            HudStat::Prices => match market {
+                Some((name, market, ..)) => {
+                    let prices: Vec<_> = GameResource::ALL
+                        .iter()
+                        .map(|resource| format!("{resource:?} ${}", market.price(*resource)))
+                        .collect();
+                    format!("{name}: {}", prices.join("  "))
+                }
+                None => "Hover over a consumer".to_string(),
+            },
+        };
+
+        if text.sections[1].value != value {
+            text.sections[1].value = value;
+        }
+    }
+}
+
+fn update_hud_objectives(
+    progress: Res<ObjectiveProgress>,
+    mut objective_query: Query<(&HudObjective, &mut Text)>,
+) {
+    for (objective, mut text) in &mut objective_query {
+        let Some(state) = progress.0.get(objective.0) else {
+            continue;
+        };
+
+        let value = if state.is_met() && !matches!(state.objective, Objective::FailuresBelow(_)) {
+            "Done".to_string()
+        } else {
+            format!("{}/{}", state.current, state.target)
+        };
+        if text.sections[1].value != value {
+            text.sections[1].value = value;
+        }
+    }
+}
+
+fn update_event_feed(
+    mut commands: Commands,
+    events: Res<SystemEvents>,
+    mut feed_query: Query<(Entity, &mut HudEventFeed)>,
+    mut row_query: Query<(&HudEventRow, &mut Text)>,
+) {
+    for (entity, mut feed) in &mut feed_query {
+        if feed
+            .0
+            .iter()
+            .eq(events.active.iter().map(|event| &event.label))
+        {
+            continue;
+        }
+        feed.0 = events
+            .active
+            .iter()
+            .map(|event| event.label.clone())
+            .collect();
+
+        let mut feed = commands.entity(entity);
+        feed.despawn_descendants();
+        feed.with_children(|children| {
+            for (index, event) in events.active.iter().enumerate() {
+                children.readout(&event.label).insert(HudEventRow(index));
+            }
+        });
+    }
+
+    for (row, mut text) in &mut row_query {
+        let Some(event) = events.active.get(row.0) else {
+            continue;
+        };
+        let value = event.countdown();
+        if text.sections[1].value != value {
+            text.sections[1].value = value;
+        }
+    }
+}
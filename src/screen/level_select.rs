@@ -0,0 +1,88 @@
+//! Pick a campaign level to play. Levels unlock as earlier ones are completed.
+
+use bevy::prelude::*;
+
+use super::Screen;
+use crate::{
+    game::{
+        assets::{HandleMap, LevelKey},
+        campaign::{CampaignProgress, CurrentLevel, LevelStatus, CAMPAIGN},
+        spawn::level::Level,
+    },
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::LevelSelect), enter_level_select);
+
+    app.register_type::<LevelSelectAction>();
+    app.add_systems(
+        Update,
+        handle_level_select_action.run_if(in_state(Screen::LevelSelect)),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum LevelSelectAction {
+    Play(LevelKey),
+    Back,
+}
+
+fn enter_level_select(
+    mut commands: Commands,
+    progress: Res<CampaignProgress>,
+    level_handles: Res<HandleMap<LevelKey>>,
+    levels: Res<Assets<Level>>,
+) {
+    commands
+        .ui_root()
+        .insert(StateScoped(Screen::LevelSelect))
+        .with_children(|children| {
+            children.header("Campaign");
+            for (index, key) in CAMPAIGN.into_iter().enumerate() {
+                let name = levels
+                    .get(&level_handles[&key])
+                    .map_or("Unknown", |level| level.name.as_str());
+                let title = format!("{}. {name}", index + 1);
+
+                match progress.status(key) {
+                    LevelStatus::Locked => {
+                        children.label(format!("{title} (Locked)"));
+                    }
+                    LevelStatus::Unlocked => {
+                        children
+                            .small_button(title)
+                            .insert(LevelSelectAction::Play(key));
+                    }
+                    LevelStatus::Completed => {
+                        let best = progress.best_score(key).unwrap_or_default();
+                        let stars = progress.best_stars(key).unwrap_or_default();
+                        children
+                            .small_button(title)
+                            .insert(LevelSelectAction::Play(key));
+                        children.label(format!("Best {best} - {stars}/3 stars"));
+                    }
+                }
+            }
+            children.button("Back").insert(LevelSelectAction::Back);
+        });
+}
+
+fn handle_level_select_action(
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut button_query: InteractionQuery<&LevelSelectAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                LevelSelectAction::Play(key) => {
+                    current_level.0 = *key;
+                    next_screen.set(Screen::Playing);
+                }
+                LevelSelectAction::Back => next_screen.set(Screen::Title),
+            }
+        }
+    }
+}
@@ -5,36 +5,144 @@ use bevy::prelude::*;
 
 use super::Screen;
 use crate::{
-    game::assets::{HandleMap, ImageKey, SfxKey, SoundtrackKey},
+    game::assets::{HandleMap, ImageKey, LevelKey, LoadProgress, SfxKey, SoundtrackKey},
     ui::prelude::*,
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Loading), enter_loading);
+    app.register_type::<(LoadingCategory, LoadingErrors)>();
     app.add_systems(
         Update,
-        continue_to_title.run_if(in_state(Screen::Loading).and_then(all_assets_loaded)),
+        (
+            update_loading_status,
+            continue_to_title.run_if(all_assets_loaded),
+        )
+            .chain()
+            .run_if(in_state(Screen::Loading)),
     );
 }
 
+/// The kinds of asset listed in the manifest, shown with their own counts.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum LoadingCategory {
+    Images,
+    Sfx,
+    Soundtracks,
+    Levels,
+}
+
+impl LoadingCategory {
+    const ALL: [Self; 4] = [Self::Images, Self::Sfx, Self::Soundtracks, Self::Levels];
+
+    fn label(&self) -> &'static str {
+        match self {
+            LoadingCategory::Images => "Images",
+            LoadingCategory::Sfx => "Sound Effects",
+            LoadingCategory::Soundtracks => "Soundtracks",
+            LoadingCategory::Levels => "Levels",
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct LoadingErrors;
+
 fn enter_loading(mut commands: Commands) {
     commands
         .ui_root()
         .insert(StateScoped(Screen::Loading))
         .with_children(|children| {
             children.label("Loading...");
+            children.progress_bar(0.0);
+            for category in LoadingCategory::ALL {
+                children.readout(category.label()).insert(category);
+            }
+            children.spawn((
+                Name::new("Loading Errors"),
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: ui_palette::ERROR_TEXT,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    max_width: Val::Px(600.0),
+                    ..default()
+                }),
+                LoadingErrors,
+            ));
         });
 }
 
-fn all_assets_loaded(
-    asset_server: Res<AssetServer>,
-    image_handles: Res<HandleMap<ImageKey>>,
-    sfx_handles: Res<HandleMap<SfxKey>>,
-    soundtrack_handles: Res<HandleMap<SoundtrackKey>>,
-) -> bool {
-    image_handles.all_loaded(&asset_server)
-        && sfx_handles.all_loaded(&asset_server)
-        && soundtrack_handles.all_loaded(&asset_server)
+/// Polls every [`HandleMap`] listed in the asset manifest.
+#[derive(bevy::ecs::system::SystemParam)]
+struct AssetProgress<'w> {
+    asset_server: Res<'w, AssetServer>,
+    image_handles: Res<'w, HandleMap<ImageKey>>,
+    sfx_handles: Res<'w, HandleMap<SfxKey>>,
+    soundtrack_handles: Res<'w, HandleMap<SoundtrackKey>>,
+    level_handles: Res<'w, HandleMap<LevelKey>>,
+}
+
+impl AssetProgress<'_> {
+    fn get(&self, category: LoadingCategory) -> LoadProgress {
+        match category {
+            LoadingCategory::Images => self.image_handles.progress(&self.asset_server),
+            LoadingCategory::Sfx => self.sfx_handles.progress(&self.asset_server),
+            LoadingCategory::Soundtracks => self.soundtrack_handles.progress(&self.asset_server),
+            LoadingCategory::Levels => self.level_handles.progress(&self.asset_server),
+        }
+    }
+}
+
+fn update_loading_status(
+    progress: AssetProgress,
+    mut bar_query: Query<&mut ProgressBar>,
+    mut readout_query: Query<(&LoadingCategory, &mut Text), Without<LoadingErrors>>,
+    mut error_query: Query<&mut Text, With<LoadingErrors>>,
+) {
+    let (mut loaded, mut total, mut errors) = (0, 0, Vec::new());
+    for (category, mut text) in &mut readout_query {
+        let category_progress = progress.get(*category);
+        text.sections[1].value = if category_progress.errors.is_empty() {
+            format!("{}/{}", category_progress.loaded, category_progress.total)
+        } else {
+            format!(
+                "{}/{} ({} failed)",
+                category_progress.loaded,
+                category_progress.total,
+                category_progress.errors.len()
+            )
+        };
+
+        loaded += category_progress.loaded;
+        total += category_progress.total;
+        errors.extend(category_progress.errors);
+    }
+
+    for mut bar in &mut bar_query {
+        bar.0 = if total == 0 {
+            1.0
+        } else {
+            loaded as f32 / total as f32
+        };
+    }
+
+    // A failed asset never finishes loading, so say so rather than waiting forever.
+    for mut text in &mut error_query {
+        text.sections[0].value = errors.join("\n");
+    }
+}
+
+fn all_assets_loaded(progress: AssetProgress) -> bool {
+    LoadingCategory::ALL
+        .into_iter()
+        .all(|category| progress.get(category).is_done())
 }
 
 fn continue_to_title(mut next_screen: ResMut<NextState<Screen>>) {
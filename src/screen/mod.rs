@@ -1,10 +1,21 @@
 //! The game's main screen states and transitions between them.
 
 mod credits;
+mod editor;
+mod forecast;
+mod hud;
+mod level_select;
 mod loading;
+mod pause;
 mod playing;
+mod records;
+mod research;
+mod results;
+mod settings;
 mod splash;
 mod title;
+mod tutorial;
+mod victory;
 
 use bevy::prelude::*;
 
@@ -12,22 +23,52 @@ pub(super) fn plugin(app: &mut App) {
     app.init_state::<Screen>();
     app.enable_state_scoped_entities::<Screen>();
 
+    app.init_state::<Menu>();
+    app.enable_state_scoped_entities::<Menu>();
+
     app.add_plugins((
         splash::plugin,
         loading::plugin,
         title::plugin,
+        level_select::plugin,
         credits::plugin,
         playing::plugin,
+        hud::plugin,
+        pause::plugin,
+        settings::plugin,
+        victory::plugin,
+        results::plugin,
+        editor::plugin,
+        tutorial::plugin,
+        forecast::plugin,
     ));
+
+    // Menus opened on top of other screens.
+    app.add_plugins((research::plugin, records::plugin));
 }
 
 /// The game's main screen states.
 #[derive(States, Debug, Hash, PartialEq, Eq, Clone, Default)]
 pub enum Screen {
+    // Levels are assets now, so the game has to pass through the loading screen.
+    #[default]
     Splash,
     Loading,
     Title,
+    LevelSelect,
     Credits,
-    #[default]
     Playing,
+    Victory,
+    Results,
+}
+
+/// Menus that can be opened on top of the current screen.
+#[derive(States, Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Menu {
+    #[default]
+    None,
+    Pause,
+    Settings,
+    Research,
+    Records,
 }
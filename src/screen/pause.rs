@@ -0,0 +1,81 @@
+//! The pause menu that opens on top of the playing screen.
+
+use bevy::prelude::*;
+
+use super::{Menu, Screen};
+use crate::{
+    settings::{BindingAction, Settings},
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Pause), enter_pause);
+    app.add_systems(OnExit(Screen::Playing), close_menu);
+
+    app.register_type::<PauseAction>();
+    app.add_systems(
+        Update,
+        (
+            toggle_pause_menu.run_if(in_state(Screen::Playing)),
+            handle_pause_action.run_if(in_state(Menu::Pause)),
+        ),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum PauseAction {
+    Resume,
+    Settings,
+    Quit,
+}
+
+fn enter_pause(mut commands: Commands) {
+    commands
+        .overlay_root()
+        .insert(StateScoped(Menu::Pause))
+        .with_children(|children| {
+            children.header("Paused");
+            children.button("Resume").insert(PauseAction::Resume);
+            children.button("Settings").insert(PauseAction::Settings);
+            children.button("Quit").insert(PauseAction::Quit);
+        });
+}
+
+fn close_menu(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::None);
+}
+
+fn toggle_pause_menu(
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    menu: Res<State<Menu>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    if !input.just_pressed(settings.key_bindings.get(BindingAction::PauseMenu)) {
+        return;
+    }
+
+    match menu.get() {
+        Menu::None => next_menu.set(Menu::Pause),
+        Menu::Pause | Menu::Research => next_menu.set(Menu::None),
+        // The settings menu handles its own way back.
+        Menu::Settings | Menu::Records => (),
+    }
+}
+
+fn handle_pause_action(
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut button_query: InteractionQuery<&PauseAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                PauseAction::Resume => next_menu.set(Menu::None),
+                PauseAction::Settings => next_menu.set(Menu::Settings),
+                PauseAction::Quit => next_screen.set(Screen::Title),
+            }
+        }
+    }
+}
@@ -4,22 +4,17 @@ use bevy::prelude::*;
 
 use super::Screen;
 use crate::game::{
-    assets::SoundtrackKey, audio::soundtrack::PlaySoundtrack, spawn::level::SpawnLevel,
+    assets::SoundtrackKey, audio::soundtrack::PlaySoundtrack, campaign::CurrentLevel,
+    spawn::level::SpawnLevel,
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Playing), enter_playing);
     app.add_systems(OnExit(Screen::Playing), exit_playing);
-
-    // app.add_systems(
-    //     Update,
-    //     return_to_title_screen
-    //         .run_if(in_state(Screen::Playing).and_then(input_just_pressed(KeyCode::Escape))),
-    // );
 }
 
-fn enter_playing(mut commands: Commands) {
-    commands.trigger(SpawnLevel);
+fn enter_playing(mut commands: Commands, current_level: Res<CurrentLevel>) {
+    commands.trigger(SpawnLevel(current_level.0));
     commands.trigger(PlaySoundtrack::Key(SoundtrackKey::Gameplay));
 }
 
@@ -27,7 +22,3 @@ fn exit_playing(mut commands: Commands) {
     // We could use [`StateScoped`] on the sound playing entites instead.
     commands.trigger(PlaySoundtrack::Disable);
 }
-
-fn _return_to_title_screen(mut next_screen: ResMut<NextState<Screen>>) {
-    next_screen.set(Screen::Title);
-}
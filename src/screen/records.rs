@@ -0,0 +1,101 @@
+//! The history of recent runs, opened from the title screen.
+
+use bevy::prelude::*;
+
+use super::Menu;
+use crate::{
+    game::{
+        assets::{HandleMap, LevelKey},
+        high_scores::{HighScores, RunRecord},
+        spawn::level::Level,
+    },
+    settings::{BindingAction, Settings},
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Records), enter_records);
+
+    app.register_type::<RecordsAction>();
+    app.add_systems(
+        Update,
+        handle_records_action.run_if(in_state(Menu::Records)),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum RecordsAction {
+    Back,
+}
+
+/// The name of the level a run was played on. Daily levels are generated,
+/// so older ones aren't around anymore.
+pub(super) fn level_name<'a>(
+    run: &RunRecord,
+    level_handles: &HandleMap<LevelKey>,
+    levels: &'a Assets<Level>,
+) -> &'a str {
+    level_handles
+        .get(&run.level)
+        .and_then(|handle| levels.get(handle))
+        .map_or("Daily", |level| level.name.as_str())
+}
+
+/// One line per run: where and when it was played, and how it went.
+pub(super) fn run_row(run: &RunRecord, name: &str) -> String {
+    format!(
+        "{name}  {}  {}  {} delivered  {} failed  {}",
+        run.score,
+        run.duration_label(),
+        run.deliveries,
+        run.failures,
+        run.date
+    )
+}
+
+fn enter_records(
+    mut commands: Commands,
+    high_scores: Res<HighScores>,
+    level_handles: Res<HandleMap<LevelKey>>,
+    levels: Res<Assets<Level>>,
+) {
+    commands
+        .overlay_root()
+        .insert(StateScoped(Menu::Records))
+        .with_children(|children| {
+            children.panel("Recent Runs").with_children(|children| {
+                if high_scores.history().is_empty() {
+                    children.label("No runs finished yet");
+                }
+                children.scroll_list(420.0).with_children(|children| {
+                    for run in high_scores.history() {
+                        let name = level_name(run, &level_handles, &levels);
+                        let outcome = if run.completed { "Won" } else { "Lost" };
+                        children.label(format!("{outcome}  {}", run_row(run, name)));
+                    }
+                });
+                children.button("Back").insert(RecordsAction::Back);
+            });
+        });
+}
+
+fn handle_records_action(
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut button_query: InteractionQuery<&RecordsAction>,
+) {
+    let mut back = input.just_pressed(settings.key_bindings.get(BindingAction::PauseMenu));
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                RecordsAction::Back => back = true,
+            }
+        }
+    }
+
+    if back {
+        next_menu.set(Menu::None);
+    }
+}
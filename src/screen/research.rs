@@ -0,0 +1,132 @@
+//! The research tree, opened on top of the playing screen.
+
+use bevy::prelude::*;
+
+use super::{Menu, Screen};
+use crate::{
+    game::{
+        ledger::Ledger,
+        research::{Research, ResearchNode, ResearchRequested},
+    },
+    settings::{BindingAction, Settings},
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Research), enter_research);
+
+    app.register_type::<(ResearchAction, ResearchFunds)>();
+    app.add_systems(
+        Update,
+        (
+            toggle_research_menu.run_if(in_state(Screen::Playing)),
+            (handle_research_action, update_research_buttons)
+                .chain()
+                .run_if(in_state(Menu::Research)),
+        ),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum ResearchAction {
+    Research(ResearchNode),
+    Close,
+}
+
+/// Shows the money there is to spend on research.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct ResearchFunds;
+
+fn enter_research(mut commands: Commands) {
+    commands
+        .overlay_root()
+        .insert(StateScoped(Menu::Research))
+        .with_children(|children| {
+            children.panel("Research").with_children(|children| {
+                children.readout("Funds").insert(ResearchFunds);
+                children.scroll_list(420.0).with_children(|children| {
+                    for (branch, nodes) in ResearchNode::BRANCHES {
+                        children.header(branch);
+                        for node in nodes {
+                            children.small_button(node.label()).insert((
+                                ResearchAction::Research(*node),
+                                Tooltip::new(node.description()),
+                            ));
+                        }
+                    }
+                });
+                children.button("Close").insert(ResearchAction::Close);
+            });
+        });
+}
+
+fn toggle_research_menu(
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    menu: Res<State<Menu>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    if !input.just_pressed(settings.key_bindings.get(BindingAction::Research)) {
+        return;
+    }
+
+    match menu.get() {
+        Menu::None => next_menu.set(Menu::Research),
+        Menu::Research => next_menu.set(Menu::None),
+        Menu::Pause | Menu::Settings | Menu::Records => (),
+    }
+}
+
+fn handle_research_action(
+    mut commands: Commands,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut button_query: InteractionQuery<&ResearchAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                ResearchAction::Research(node) => commands.trigger(ResearchRequested(*node)),
+                ResearchAction::Close => next_menu.set(Menu::None),
+            }
+        }
+    }
+}
+
+/// Every node shows whether it is done, what it costs, or what it is waiting for.
+fn update_research_buttons(
+    research: Res<Research>,
+    ledger: Res<Ledger>,
+    mut funds_query: Query<&mut Text, With<ResearchFunds>>,
+    button_query: Query<(&ResearchAction, &Children)>,
+    mut text_query: Query<&mut Text, Without<ResearchFunds>>,
+) {
+    for mut text in &mut funds_query {
+        let value = format!("${}", ledger.money);
+        if text.sections[1].value != value {
+            text.sections[1].value = value;
+        }
+    }
+
+    for (action, children) in &button_query {
+        let ResearchAction::Research(node) = action else {
+            continue;
+        };
+        let value = if research.is_unlocked(*node) {
+            format!("{} (done)", node.label())
+        } else if research.is_available(*node) {
+            format!("{} (${})", node.label(), node.cost())
+        } else {
+            format!("{} (locked)", node.label())
+        };
+
+        for child in children {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                if text.sections[0].value != value {
+                    text.sections[0].value.clone_from(&value);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,345 @@
+//! A summary of the run that just ended, drawn as simple charts.
+
+use bevy::prelude::*;
+use bevy_vector_shapes::{
+    painter::ShapePainter,
+    shapes::{LinePainter, RectPainter},
+};
+
+use super::Screen;
+use crate::{
+    clipboard,
+    game::{
+        campaign::{CurrentLevel, CAMPAIGN},
+        daily::DailyResult,
+        high_scores::{HighScores, LatestRun},
+        statistics::RunStatistics,
+    },
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Results), enter_results);
+
+    app.register_type::<(ResultsAction, CopyStatusLabel)>();
+    app.add_systems(
+        Update,
+        (handle_results_action, update_copy_status, draw_charts).run_if(in_state(Screen::Results)),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum ResultsAction {
+    Continue,
+    CopyDailyResult,
+}
+
+/// Tells whether the daily result made it onto the clipboard.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct CopyStatusLabel;
+
+/// How many entries the bar charts show at most.
+const MAX_BARS: usize = 5;
+
+const CHART_SIZE: Vec2 = Vec2::new(300.0, 180.0);
+
+/// Where each chart is drawn, in world space around the camera.
+#[derive(Debug, Clone, Copy)]
+enum Chart {
+    Deliveries,
+    Money,
+    Failures,
+    Traffic,
+}
+
+impl Chart {
+    const ALL: [Self; 4] = [Self::Deliveries, Self::Money, Self::Failures, Self::Traffic];
+
+    fn center(&self) -> Vec2 {
+        match self {
+            Chart::Deliveries => Vec2::new(-170.0, 120.0),
+            Chart::Money => Vec2::new(170.0, 120.0),
+            Chart::Failures => Vec2::new(-170.0, -165.0),
+            Chart::Traffic => Vec2::new(170.0, -165.0),
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Chart::Deliveries => "Deliveries over time",
+            Chart::Money => "Money over time",
+            Chart::Failures => "Failed orders per satellite",
+            Chart::Traffic => "Busiest connections",
+        }
+    }
+
+    fn bottom_left(&self) -> Vec2 {
+        self.center() - CHART_SIZE / 2.0
+    }
+}
+
+fn enter_results(
+    mut commands: Commands,
+    statistics: Res<RunStatistics>,
+    latest_run: Res<LatestRun>,
+    high_scores: Res<HighScores>,
+    daily_result: Res<DailyResult>,
+) {
+    commands
+        .hud_root()
+        .insert(StateScoped(Screen::Results))
+        .with_children(|children| {
+            children.toolbar().with_children(|children| {
+                children.header("Run Summary");
+                children
+                    .small_button("Continue")
+                    .insert(ResultsAction::Continue);
+                if daily_result.summary.is_some() {
+                    children
+                        .small_button("Copy Result")
+                        .insert(ResultsAction::CopyDailyResult);
+                }
+            });
+
+            if daily_result.summary.is_some() {
+                children.label("").insert(CopyStatusLabel);
+            }
+
+            if let Some(run) = &latest_run.record {
+                let best = high_scores
+                    .best_for(run.level, run.seed)
+                    .map_or(run.score, |best| best.score);
+                children.label(format!(
+                    "Score {}  Time {}  Seed {:08x}",
+                    run.score,
+                    run.duration_label(),
+                    run.seed
+                ));
+                children.label(if latest_run.new_best {
+                    "New best for this level and seed!".to_string()
+                } else {
+                    format!("Best for this level and seed: {best}")
+                });
+            }
+        });
+
+    // Text can't be drawn with the painter, so titles and bar labels are spawned once.
+    for chart in Chart::ALL {
+        spawn_chart_text(
+            &mut commands,
+            chart.title(),
+            chart.center() + Vec2::Y * (CHART_SIZE.y / 2.0 + 14.0),
+            16.0,
+        );
+    }
+
+    let failures = RunStatistics::top(&statistics.failures_by_satellite, MAX_BARS);
+    for (index, (name, _)) in failures.iter().enumerate() {
+        spawn_chart_text(
+            &mut commands,
+            name,
+            bar_label_position(Chart::Failures, index, failures.len()),
+            11.0,
+        );
+    }
+
+    let traffic = RunStatistics::top(&statistics.connection_traffic, MAX_BARS);
+    for (index, ((a, b), _)) in traffic.iter().enumerate() {
+        spawn_chart_text(
+            &mut commands,
+            &format!("{a}\n{b}"),
+            bar_label_position(Chart::Traffic, index, traffic.len()),
+            11.0,
+        );
+    }
+}
+
+fn spawn_chart_text(commands: &mut Commands, text: &str, position: Vec2, font_size: f32) {
+    commands.spawn((
+        Name::new("Chart Text"),
+        Text2dBundle {
+            text: Text::from_section(
+                text,
+                TextStyle {
+                    font_size,
+                    color: ui_palette::LABEL_TEXT,
+                    ..default()
+                },
+            )
+            .with_justify(JustifyText::Center),
+            transform: Transform::from_translation(position.extend(1.0)),
+            ..default()
+        },
+        StateScoped(Screen::Results),
+    ));
+}
+
+fn handle_results_action(
+    mut next_screen: ResMut<NextState<Screen>>,
+    current_level: Res<CurrentLevel>,
+    mut daily_result: ResMut<DailyResult>,
+    mut button_query: InteractionQuery<&ResultsAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                // Only campaign levels are picked from the level select screen.
+                ResultsAction::Continue if !CAMPAIGN.contains(&current_level.0) => {
+                    next_screen.set(Screen::Title)
+                }
+                ResultsAction::Continue => next_screen.set(Screen::LevelSelect),
+                ResultsAction::CopyDailyResult => {
+                    if let Some(summary) = &daily_result.summary {
+                        daily_result.copy = Some(clipboard::copy(summary));
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn update_copy_status(
+    daily_result: Res<DailyResult>,
+    label_query: Query<&Children, With<CopyStatusLabel>>,
+    mut text_query: Query<&mut Text>,
+) {
+    let value = match daily_result.copy.as_ref().map(|copy| copy.result()) {
+        None => "",
+        Some(None) => "Copying...",
+        Some(Some(true)) => "Daily result copied to the clipboard",
+        Some(Some(false)) => "Could not copy the daily result",
+    };
+    for children in &label_query {
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            if text.sections[0].value != value {
+                text.sections[0].value = value.to_string();
+            }
+        }
+    }
+}
+
+fn draw_charts(mut painter: ShapePainter, statistics: Res<RunStatistics>) {
+    for chart in Chart::ALL {
+        draw_chart_background(&mut painter, chart);
+    }
+
+    let elapsed: Vec<f32> = statistics
+        .samples
+        .iter()
+        .map(|sample| sample.elapsed)
+        .collect();
+    let deliveries: Vec<f32> = statistics
+        .samples
+        .iter()
+        .map(|sample| sample.deliveries as f32)
+        .collect();
+    let money: Vec<f32> = statistics
+        .samples
+        .iter()
+        .map(|sample| sample.money as f32)
+        .collect();
+    draw_line_chart(
+        &mut painter,
+        Chart::Deliveries,
+        &elapsed,
+        &deliveries,
+        ui_palette::CONTROL_FILL,
+    );
+    draw_line_chart(
+        &mut painter,
+        Chart::Money,
+        &elapsed,
+        &money,
+        ui_palette::CONTROL_FILL,
+    );
+
+    let failures = RunStatistics::top(&statistics.failures_by_satellite, MAX_BARS);
+    draw_bar_chart(
+        &mut painter,
+        Chart::Failures,
+        &failures.iter().map(|(_, count)| *count).collect::<Vec<_>>(),
+        ui_palette::ERROR_TEXT,
+    );
+    let traffic = RunStatistics::top(&statistics.connection_traffic, MAX_BARS);
+    draw_bar_chart(
+        &mut painter,
+        Chart::Traffic,
+        &traffic.iter().map(|(_, count)| *count).collect::<Vec<_>>(),
+        ui_palette::CONTROL_FILL,
+    );
+
+    painter.set_translation(Vec3::ZERO);
+}
+
+fn draw_chart_background(painter: &mut ShapePainter, chart: Chart) {
+    painter.hollow = false;
+    painter.set_color(ui_palette::CONTROL_TRACK);
+    painter.set_translation(chart.center().extend(0.0));
+    painter.rect(CHART_SIZE);
+    painter.set_translation(Vec3::ZERO);
+}
+
+/// Plot `values` against `times`, scaled to fill the chart.
+/// The baseline sits at zero, or at the lowest value if that is negative.
+fn draw_line_chart(
+    painter: &mut ShapePainter,
+    chart: Chart,
+    times: &[f32],
+    values: &[f32],
+    color: Color,
+) {
+    if values.len() < 2 {
+        return;
+    }
+    let last_time = times[times.len() - 1];
+    let min = values.iter().copied().fold(0.0, f32::min);
+    let max = values.iter().copied().fold(min + 1.0, f32::max);
+
+    let origin = chart.bottom_left();
+    let point = |time: f32, value: f32| {
+        (origin
+            + Vec2::new(
+                time / last_time.max(f32::EPSILON) * CHART_SIZE.x,
+                (value - min) / (max - min) * CHART_SIZE.y,
+            ))
+        .extend(0.5)
+    };
+
+    painter.thickness = 1.0;
+    painter.set_color(ui_palette::LABEL_TEXT);
+    painter.line(point(0.0, 0.0), point(last_time, 0.0));
+
+    painter.thickness = 2.0;
+    painter.set_color(color);
+    for i in 1..values.len() {
+        painter.line(
+            point(times[i - 1], values[i - 1]),
+            point(times[i], values[i]),
+        );
+    }
+}
+
+fn bar_label_position(chart: Chart, index: usize, count: usize) -> Vec2 {
+    let slot = CHART_SIZE.x / count as f32;
+    chart.bottom_left() + Vec2::new(slot * (index as f32 + 0.5), -14.0)
+}
+
+fn draw_bar_chart(painter: &mut ShapePainter, chart: Chart, values: &[u32], color: Color) {
+    let Some(&max) = values.iter().max() else {
+        return;
+    };
+
+    let slot = CHART_SIZE.x / values.len() as f32;
+    painter.hollow = false;
+    painter.set_color(color);
+    for (index, value) in values.iter().enumerate() {
+        let height = *value as f32 / max.max(1) as f32 * CHART_SIZE.y;
+        let center = chart.bottom_left() + Vec2::new(slot * (index as f32 + 0.5), height / 2.0);
+        painter.set_translation(center.extend(0.5));
+        painter.rect(Vec2::new(slot * 0.6, height));
+    }
+    painter.set_translation(Vec3::ZERO);
+}
@@ -0,0 +1,281 @@
+//! The settings menu, opened from the title screen or the pause menu.
+
+use bevy::prelude::*;
+
+use super::{Menu, Screen};
+use crate::{
+    game::clock::TIME_SCALES,
+    settings::{BindingAction, Settings, WINDOW_SIZES},
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Settings), enter_settings);
+    app.add_systems(OnExit(Menu::Settings), save_settings);
+
+    app.init_resource::<Rebinding>();
+    app.register_type::<(SettingsAction, SettingsControl, RebindButton)>();
+    app.add_systems(
+        Update,
+        (
+            apply_sliders,
+            apply_toggles,
+            apply_dropdowns,
+            start_rebinding,
+            record_rebinding,
+            update_binding_labels,
+            handle_settings_action,
+        )
+            .chain()
+            .run_if(in_state(Menu::Settings)),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum SettingsAction {
+    Back,
+}
+
+/// Which setting a slider, toggle or dropdown edits.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum SettingsControl {
+    MasterVolume,
+    MusicVolume,
+    UiVolume,
+    SfxVolume,
+    Fullscreen,
+    WindowSize,
+    Bloom,
+    ColourBlind,
+    TimeScale,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct RebindButton(BindingAction);
+
+/// The action waiting for the player to press its new key, if any.
+#[derive(Resource, Default)]
+struct Rebinding(Option<BindingAction>);
+
+fn enter_settings(mut commands: Commands, settings: Res<Settings>) {
+    commands
+        .overlay_root()
+        .insert(StateScoped(Menu::Settings))
+        .with_children(|children| {
+            children.panel("Settings").with_children(|children| {
+                children.scroll_list(480.0).with_children(|children| {
+                    children.header("Audio");
+                    children.label("Master Volume");
+                    children
+                        .slider(settings.master_volume, 0.0, 1.0)
+                        .insert(SettingsControl::MasterVolume);
+                    children.label("Music Volume");
+                    children
+                        .slider(settings.music_volume, 0.0, 1.0)
+                        .insert(SettingsControl::MusicVolume);
+                    children.label("Interface Volume");
+                    children
+                        .slider(settings.ui_volume, 0.0, 1.0)
+                        .insert(SettingsControl::UiVolume);
+                    children.label("Sound Effects Volume");
+                    children
+                        .slider(settings.sfx_volume, 0.0, 1.0)
+                        .insert(SettingsControl::SfxVolume);
+
+                    children.header("Video");
+                    #[cfg(not(target_family = "wasm"))]
+                    {
+                        children
+                            .toggle("Fullscreen", settings.fullscreen)
+                            .insert(SettingsControl::Fullscreen);
+                        children
+                            .dropdown(
+                                WINDOW_SIZES
+                                    .iter()
+                                    .map(|(width, height)| format!("{width} x {height}"))
+                                    .collect(),
+                                settings.window_size,
+                            )
+                            .insert(SettingsControl::WindowSize);
+                    }
+                    children
+                        .toggle("Bloom", settings.bloom)
+                        .insert(SettingsControl::Bloom);
+                    children
+                        .toggle("Colour-blind palette", settings.colour_blind)
+                        .insert(SettingsControl::ColourBlind);
+
+                    children.header("Gameplay");
+                    children.label("Starting Speed");
+                    children
+                        .dropdown(
+                            TIME_SCALES
+                                .iter()
+                                .map(|speed| format!("x{speed}"))
+                                .collect(),
+                            TIME_SCALES
+                                .iter()
+                                .position(|speed| *speed == settings.time_scale)
+                                .unwrap_or_default(),
+                        )
+                        .insert(SettingsControl::TimeScale);
+
+                    children.header("Key Bindings");
+                    for action in BindingAction::ALL {
+                        children
+                            .small_button(binding_text(&settings, action))
+                            .insert(RebindButton(action));
+                    }
+                });
+
+                children.button("Back").insert(SettingsAction::Back);
+            });
+        });
+}
+
+fn save_settings(settings: Res<Settings>, mut rebinding: ResMut<Rebinding>) {
+    rebinding.0 = None;
+    settings.save();
+}
+
+fn binding_text(settings: &Settings, action: BindingAction) -> String {
+    format!(
+        "{}: {:?}",
+        action.label(),
+        settings.key_bindings.get(action)
+    )
+}
+
+fn apply_sliders(
+    slider_query: Query<(&SettingsControl, &Slider), Changed<Slider>>,
+    mut settings: ResMut<Settings>,
+) {
+    let mut updated = settings.clone();
+    for (control, slider) in &slider_query {
+        match control {
+            SettingsControl::MasterVolume => updated.master_volume = slider.value,
+            SettingsControl::MusicVolume => updated.music_volume = slider.value,
+            SettingsControl::UiVolume => updated.ui_volume = slider.value,
+            SettingsControl::SfxVolume => updated.sfx_volume = slider.value,
+            _ => (),
+        }
+    }
+    settings.set_if_neq(updated);
+}
+
+fn apply_toggles(
+    toggle_query: Query<(&SettingsControl, &Toggle), Changed<Toggle>>,
+    mut settings: ResMut<Settings>,
+) {
+    let mut updated = settings.clone();
+    for (control, toggle) in &toggle_query {
+        match control {
+            SettingsControl::Fullscreen => updated.fullscreen = toggle.0,
+            SettingsControl::Bloom => updated.bloom = toggle.0,
+            SettingsControl::ColourBlind => updated.colour_blind = toggle.0,
+            _ => (),
+        }
+    }
+    settings.set_if_neq(updated);
+}
+
+fn apply_dropdowns(
+    dropdown_query: Query<(&SettingsControl, &Dropdown), Changed<Dropdown>>,
+    mut settings: ResMut<Settings>,
+) {
+    let mut updated = settings.clone();
+    for (control, dropdown) in &dropdown_query {
+        match control {
+            SettingsControl::WindowSize => updated.window_size = dropdown.selected,
+            SettingsControl::TimeScale => {
+                updated.time_scale = TIME_SCALES[dropdown.selected.min(TIME_SCALES.len() - 1)];
+            }
+            _ => (),
+        }
+    }
+    settings.set_if_neq(updated);
+}
+
+fn start_rebinding(
+    button_query: InteractionQuery<&RebindButton>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    for (interaction, button) in &button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            rebinding.0 = Some(button.0);
+        }
+    }
+}
+
+fn record_rebinding(
+    input: Res<ButtonInput<KeyCode>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut settings: ResMut<Settings>,
+) {
+    let Some(action) = rebinding.0 else {
+        return;
+    };
+
+    if let Some(key) = input.get_just_pressed().next() {
+        settings.key_bindings.set(action, *key);
+        rebinding.0 = None;
+    }
+}
+
+fn update_binding_labels(
+    settings: Res<Settings>,
+    rebinding: Res<Rebinding>,
+    button_query: Query<(&RebindButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !settings.is_changed() && !rebinding.is_changed() {
+        return;
+    }
+
+    for (button, children) in &button_query {
+        let value = if rebinding.0 == Some(button.0) {
+            format!("{}: press a key...", button.0.label())
+        } else {
+            binding_text(&settings, button.0)
+        };
+
+        for child in children {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value.clone_from(&value);
+            }
+        }
+    }
+}
+
+fn handle_settings_action(
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    rebinding: Res<Rebinding>,
+    screen: Res<State<Screen>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut button_query: InteractionQuery<&SettingsAction>,
+) {
+    let mut back = rebinding.0.is_none()
+        && !rebinding.is_changed()
+        && input.just_pressed(settings.key_bindings.get(BindingAction::PauseMenu));
+
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                SettingsAction::Back => back = true,
+            }
+        }
+    }
+
+    if back {
+        // Return to wherever the settings menu was opened from.
+        next_menu.set(if *screen.get() == Screen::Playing {
+            Menu::Pause
+        } else {
+            Menu::None
+        });
+    }
+}
@@ -2,8 +2,22 @@
 
 use bevy::prelude::*;
 
-use super::Screen;
-use crate::ui::prelude::*;
+use super::{
+    records::{level_name, run_row},
+    Menu, Screen,
+};
+use crate::{
+    calendar::Date,
+    game::{
+        assets::{HandleMap, LevelKey, SoundtrackKey},
+        audio::soundtrack::PlaySoundtrack,
+        campaign::CurrentLevel,
+        daily::{DailyAttempts, StartDaily},
+        high_scores::HighScores,
+        spawn::level::Level,
+    },
+    ui::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Title), enter_title);
@@ -16,34 +30,89 @@ pub(super) fn plugin(app: &mut App) {
 #[reflect(Component)]
 enum TitleAction {
     Play,
+    Tutorial,
+    Endless,
+    Daily,
+    Sandbox,
+    Records,
+    Settings,
     Credits,
     /// Exit doesn't work well with embedded applications.
     #[cfg(not(target_family = "wasm"))]
     Exit,
 }
 
-fn enter_title(mut commands: Commands) {
+/// How many runs the high score table on the title screen lists.
+const HIGH_SCORE_ROWS: usize = 5;
+
+fn enter_title(
+    mut commands: Commands,
+    high_scores: Res<HighScores>,
+    daily_attempts: Res<DailyAttempts>,
+    level_handles: Res<HandleMap<LevelKey>>,
+    levels: Res<Assets<Level>>,
+) {
     commands
         .ui_root()
         .insert(StateScoped(Screen::Title))
         .with_children(|children| {
             children.button("Play").insert(TitleAction::Play);
+            children.button("Tutorial").insert(TitleAction::Tutorial);
+            children.button("Endless").insert(TitleAction::Endless);
+            children.button("Sandbox").insert(TitleAction::Sandbox);
+            if daily_attempts.remaining(Date::today()) > 0 {
+                children.button("Daily").insert(TitleAction::Daily);
+            } else {
+                children.label("Daily challenge played - back tomorrow");
+            }
+            children.button("Records").insert(TitleAction::Records);
+            children.button("Settings").insert(TitleAction::Settings);
             children.button("Credits").insert(TitleAction::Credits);
 
             #[cfg(not(target_family = "wasm"))]
             children.button("Exit").insert(TitleAction::Exit);
+
+            let top = high_scores.top(HIGH_SCORE_ROWS);
+            if !top.is_empty() {
+                children.panel("High Scores").with_children(|children| {
+                    for run in top {
+                        let name = level_name(run, &level_handles, &levels);
+                        children.label(run_row(run, name));
+                    }
+                });
+            }
         });
+
+    commands.trigger(PlaySoundtrack::Key(SoundtrackKey::Title));
 }
 
 fn handle_title_action(
+    mut commands: Commands,
     mut next_screen: ResMut<NextState<Screen>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut current_level: ResMut<CurrentLevel>,
     mut button_query: InteractionQuery<&TitleAction>,
     #[cfg(not(target_family = "wasm"))] mut app_exit: EventWriter<AppExit>,
 ) {
     for (interaction, action) in &mut button_query {
         if matches!(interaction, Interaction::Pressed) {
             match action {
-                TitleAction::Play => next_screen.set(Screen::Playing),
+                TitleAction::Play => next_screen.set(Screen::LevelSelect),
+                TitleAction::Tutorial => {
+                    current_level.0 = LevelKey::Tutorial;
+                    next_screen.set(Screen::Playing);
+                }
+                TitleAction::Endless => {
+                    current_level.0 = LevelKey::Endless;
+                    next_screen.set(Screen::Playing);
+                }
+                TitleAction::Sandbox => {
+                    current_level.0 = LevelKey::Sandbox;
+                    next_screen.set(Screen::Playing);
+                }
+                TitleAction::Daily => commands.trigger(StartDaily),
+                TitleAction::Records => next_menu.set(Menu::Records),
+                TitleAction::Settings => next_menu.set(Menu::Settings),
                 TitleAction::Credits => next_screen.set(Screen::Credits),
 
                 #[cfg(not(target_family = "wasm"))]
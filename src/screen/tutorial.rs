@@ -0,0 +1,101 @@
+//! The callout that shows the current tutorial step, with buttons to continue or skip.
+
+use bevy::prelude::*;
+
+use super::Screen;
+use crate::{
+    game::tutorial::{ContinueTutorial, SkipTutorial, Tutorial},
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(TutorialAction, TutorialCallout)>();
+    app.add_systems(OnEnter(Screen::Playing), spawn_tutorial_callout);
+    app.add_systems(
+        Update,
+        (handle_tutorial_action, rebuild_tutorial_callout)
+            .chain()
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum TutorialAction {
+    Continue,
+    Skip,
+}
+
+/// The part of the screen that is rebuilt for every tutorial step.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct TutorialCallout;
+
+fn spawn_tutorial_callout(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Tutorial Callout"),
+        TutorialCallout,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(16.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        },
+        StateScoped(Screen::Playing),
+    ));
+}
+
+fn handle_tutorial_action(
+    mut commands: Commands,
+    mut button_query: InteractionQuery<&TutorialAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                TutorialAction::Continue => commands.trigger(ContinueTutorial),
+                TutorialAction::Skip => commands.trigger(SkipTutorial),
+            }
+        }
+    }
+}
+
+fn rebuild_tutorial_callout(
+    mut commands: Commands,
+    tutorial: Res<Tutorial>,
+    callout_query: Query<(Entity, Ref<TutorialCallout>)>,
+) {
+    for (entity, callout) in &callout_query {
+        if !tutorial.is_changed() && !callout.is_added() {
+            continue;
+        }
+
+        let mut callout = commands.entity(entity);
+        callout.despawn_descendants();
+        let Some(step) = tutorial.step() else {
+            continue;
+        };
+
+        let title = format!("Tutorial {}/{}", tutorial.current + 1, tutorial.steps.len());
+        let needs_continue = step.action.needs_continue();
+        let message = step.message.clone();
+        callout.with_children(|children| {
+            children.panel(title).with_children(|children| {
+                children.label(message);
+                children.toolbar().with_children(|children| {
+                    if needs_continue {
+                        children
+                            .small_button("Continue")
+                            .insert(TutorialAction::Continue);
+                    }
+                    children
+                        .small_button("Skip Tutorial")
+                        .insert(TutorialAction::Skip);
+                });
+            });
+        });
+    }
+}
@@ -0,0 +1,95 @@
+//! The screen shown after every objective of a level is met.
+
+use bevy::prelude::*;
+
+use super::Screen;
+use crate::{
+    game::{
+        assets::{HandleMap, LevelKey},
+        campaign::{next_level, CurrentLevel},
+        clock::GameClock,
+        ledger::Ledger,
+        spawn::level::Level,
+    },
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Victory), enter_victory);
+
+    app.register_type::<VictoryAction>();
+    app.add_systems(
+        Update,
+        handle_victory_action.run_if(in_state(Screen::Victory)),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum VictoryAction {
+    NextLevel(LevelKey),
+    Retry,
+    Summary,
+    LevelSelect,
+}
+
+fn enter_victory(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    level_handles: Res<HandleMap<LevelKey>>,
+    levels: Res<Assets<Level>>,
+    ledger: Res<Ledger>,
+    clock: Res<GameClock>,
+) {
+    let level = levels.get(&level_handles[&current_level.0]);
+    let name = level.map_or("Level", |level| level.name.as_str());
+    let stars = level.map_or(1, |level| level.stars(ledger.score));
+
+    commands
+        .ui_root()
+        .insert(StateScoped(Screen::Victory))
+        .with_children(|children| {
+            children.header(format!("{name} complete!"));
+            children.header(format!("{stars}/3 stars"));
+
+            children.panel("Results").with_children(|children| {
+                children.label(format!("Score: {}", ledger.score));
+                children.label(format!("Deliveries: {}", ledger.fulfilled));
+                children.label(format!("Failed orders: {}", ledger.failed));
+                children.label(format!("Money: ${}", ledger.money));
+                children.label(format!("Cycles: {}", clock.cycle() + 1));
+            });
+
+            if let Some(next) = next_level(current_level.0) {
+                children
+                    .button("Next Level")
+                    .insert(VictoryAction::NextLevel(next));
+            }
+            // The daily challenge only gets a single attempt.
+            if current_level.0 != LevelKey::Daily {
+                children.button("Retry").insert(VictoryAction::Retry);
+            }
+            children.button("Summary").insert(VictoryAction::Summary);
+            children.button("Levels").insert(VictoryAction::LevelSelect);
+        });
+}
+
+fn handle_victory_action(
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut button_query: InteractionQuery<&VictoryAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                VictoryAction::NextLevel(key) => {
+                    current_level.0 = *key;
+                    next_screen.set(Screen::Playing);
+                }
+                VictoryAction::Retry => next_screen.set(Screen::Playing),
+                VictoryAction::Summary => next_screen.set(Screen::Results),
+                VictoryAction::LevelSelect => next_screen.set(Screen::LevelSelect),
+            }
+        }
+    }
+}
@@ -0,0 +1,243 @@
+//! Player settings. They are loaded before the app is built so the window
+//! and audio start out configured, and saved whenever the settings menu closes.
+
+use bevy::{
+    audio::Volume,
+    core_pipeline::bloom::BloomSettings,
+    prelude::*,
+    window::{PrimaryWindow, WindowMode, WindowResolution},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::persistence;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Settings>();
+    app.add_systems(
+        Update,
+        (
+            apply_window_settings,
+            apply_bloom_settings,
+            apply_master_volume,
+        )
+            .run_if(resource_changed::<Settings>),
+    );
+}
+
+const SETTINGS_KEY: &str = "settings";
+
+/// The window sizes the player can choose between.
+pub const WINDOW_SIZES: [(f32, f32); 3] = [(720.0, 720.0), (900.0, 900.0), (1080.0, 1080.0)];
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct Settings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub ui_volume: f32,
+    /// Volume of gameplay sound effects.
+    pub sfx_volume: f32,
+    pub fullscreen: bool,
+    /// Index into [`WINDOW_SIZES`].
+    pub window_size: usize,
+    pub bloom: bool,
+    pub colour_blind: bool,
+    /// The speed the simulation starts at when a level begins.
+    pub time_scale: f32,
+    pub key_bindings: KeyBindings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 0.3,
+            music_volume: 1.0,
+            ui_volume: 1.0,
+            sfx_volume: 1.0,
+            fullscreen: false,
+            window_size: 0,
+            bloom: true,
+            colour_blind: false,
+            time_scale: 1.0,
+            key_bindings: KeyBindings::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Load the saved settings, falling back to the defaults.
+    pub fn load() -> Self {
+        persistence::load(SETTINGS_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        persistence::save(SETTINGS_KEY, self);
+    }
+
+    pub fn window_resolution(&self) -> WindowResolution {
+        let (width, height) = WINDOW_SIZES[self.window_size.min(WINDOW_SIZES.len() - 1)];
+        WindowResolution::new(width, height)
+    }
+
+    pub fn window_mode(&self) -> WindowMode {
+        if self.fullscreen {
+            WindowMode::BorderlessFullscreen
+        } else {
+            WindowMode::Windowed
+        }
+    }
+
+    pub fn global_volume(&self) -> GlobalVolume {
+        GlobalVolume {
+            volume: Volume::new(self.master_volume),
+        }
+    }
+}
+
+/// An input that the player can rebind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum BindingAction {
+    PauseMenu,
+    TogglePause,
+    SpeedNormal,
+    SpeedFast,
+    SpeedFastest,
+    ToggleForecast,
+    BuildStation,
+    Research,
+    CycleLink,
+}
+
+impl BindingAction {
+    pub const ALL: [Self; 9] = [
+        Self::PauseMenu,
+        Self::TogglePause,
+        Self::SpeedNormal,
+        Self::SpeedFast,
+        Self::SpeedFastest,
+        Self::ToggleForecast,
+        Self::BuildStation,
+        Self::Research,
+        Self::CycleLink,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::PauseMenu => "Menu",
+            Self::TogglePause => "Pause",
+            Self::SpeedNormal => "Speed x1",
+            Self::SpeedFast => "Speed x2",
+            Self::SpeedFastest => "Speed x4",
+            Self::ToggleForecast => "Forecast",
+            Self::BuildStation => "Build relay",
+            Self::Research => "Research",
+            Self::CycleLink => "Link type",
+        }
+    }
+}
+
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub pause_menu: KeyCode,
+    pub toggle_pause: KeyCode,
+    pub speed_normal: KeyCode,
+    pub speed_fast: KeyCode,
+    pub speed_fastest: KeyCode,
+    pub toggle_forecast: KeyCode,
+    pub build_station: KeyCode,
+    pub research: KeyCode,
+    pub cycle_link: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            pause_menu: KeyCode::Escape,
+            toggle_pause: KeyCode::Space,
+            speed_normal: KeyCode::Digit1,
+            speed_fast: KeyCode::Digit2,
+            speed_fastest: KeyCode::Digit3,
+            toggle_forecast: KeyCode::KeyF,
+            build_station: KeyCode::KeyB,
+            research: KeyCode::KeyR,
+            cycle_link: KeyCode::KeyL,
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn get(&self, action: BindingAction) -> KeyCode {
+        match action {
+            BindingAction::PauseMenu => self.pause_menu,
+            BindingAction::TogglePause => self.toggle_pause,
+            BindingAction::SpeedNormal => self.speed_normal,
+            BindingAction::SpeedFast => self.speed_fast,
+            BindingAction::SpeedFastest => self.speed_fastest,
+            BindingAction::ToggleForecast => self.toggle_forecast,
+            BindingAction::BuildStation => self.build_station,
+            BindingAction::Research => self.research,
+            BindingAction::CycleLink => self.cycle_link,
+        }
+    }
+
+    pub fn set(&mut self, action: BindingAction, key: KeyCode) {
+        match action {
+            BindingAction::PauseMenu => self.pause_menu = key,
+            BindingAction::TogglePause => self.toggle_pause = key,
+            BindingAction::SpeedNormal => self.speed_normal = key,
+            BindingAction::SpeedFast => self.speed_fast = key,
+            BindingAction::SpeedFastest => self.speed_fastest = key,
+            BindingAction::ToggleForecast => self.toggle_forecast = key,
+            BindingAction::BuildStation => self.build_station = key,
+            BindingAction::Research => self.research = key,
+            BindingAction::CycleLink => self.cycle_link = key,
+        }
+    }
+}
+
+fn apply_window_settings(
+    settings: Res<Settings>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+
+    let mode = settings.window_mode();
+    if window.mode != mode {
+        window.mode = mode;
+    }
+
+    // Fullscreen windows take their size from the monitor.
+    let resolution = settings.window_resolution();
+    let resized = window.resolution.width() != resolution.width()
+        || window.resolution.height() != resolution.height();
+    if !settings.fullscreen && resized {
+        window
+            .resolution
+            .set(resolution.width(), resolution.height());
+    }
+}
+
+fn apply_bloom_settings(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    camera_query: Query<(Entity, Has<BloomSettings>), With<IsDefaultUiCamera>>,
+) {
+    for (camera, has_bloom) in &camera_query {
+        if settings.bloom && !has_bloom {
+            commands.entity(camera).insert(BloomSettings::default());
+        } else if !settings.bloom && has_bloom {
+            commands.entity(camera).remove::<BloomSettings>();
+        }
+    }
+}
+
+fn apply_master_volume(settings: Res<Settings>, mut global_volume: ResMut<GlobalVolume>) {
+    let volume = settings.global_volume();
+    if global_volume.volume.get() != volume.volume.get() {
+        *global_volume = volume;
+    }
+}
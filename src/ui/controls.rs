@@ -0,0 +1,325 @@
+//! Behaviour for the interactive widgets spawned through [`super::widgets::Widgets`].
+//! Each control keeps its state in a public component so screens can read it
+//! with `Changed<T>` queries and react to the player's input.
+
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+    ui::{RelativeCursorPosition, Val::*},
+};
+
+use super::{
+    interaction::{InteractionPalette, InteractionQuery},
+    palette::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<(Slider, Toggle, Dropdown, ProgressBar, ScrollList)>();
+    app.add_systems(
+        Update,
+        (
+            (drag_sliders, update_slider_fills).chain(),
+            (flip_toggles, update_toggle_indicators).chain(),
+            (
+                open_dropdowns,
+                select_dropdown_options,
+                update_dropdown_labels,
+            )
+                .chain(),
+            update_progress_fills,
+            scroll_lists,
+        ),
+    );
+}
+
+/// A horizontal slider. Dragging the track sets [`Slider::value`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct Slider {
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Slider {
+    /// How far along the track the value is, between 0 and 1.
+    pub fn fraction(&self) -> f32 {
+        if self.max > self.min {
+            ((self.value - self.min) / (self.max - self.min)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// A checkbox that flips whenever it is pressed.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct Toggle(pub bool);
+
+/// A button that opens a list of options to choose from.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct Dropdown {
+    pub options: Vec<String>,
+    pub selected: usize,
+}
+
+impl Dropdown {
+    pub fn selected_option(&self) -> &str {
+        self.options
+            .get(self.selected)
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+}
+
+/// A bar that fills up from left to right. The value is between 0 and 1.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct ProgressBar(pub f32);
+
+/// A container that clips its children and scrolls them with the mouse wheel.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Component)]
+pub struct ScrollList {
+    /// How far the content has been scrolled, in pixels.
+    pub offset: f32,
+}
+
+/// The coloured part of a [`Slider`] or [`ProgressBar`].
+#[derive(Component)]
+pub(super) struct ControlFill;
+
+/// The coloured box inside a [`Toggle`].
+#[derive(Component)]
+pub(super) struct ToggleIndicator;
+
+/// The text showing the selected option of a [`Dropdown`].
+#[derive(Component)]
+pub(super) struct DropdownLabel;
+
+/// The list of options spawned below an open [`Dropdown`].
+#[derive(Component)]
+struct DropdownMenu;
+
+#[derive(Component)]
+struct DropdownOption(usize);
+
+fn drag_sliders(mut slider_query: Query<(&Interaction, &RelativeCursorPosition, &mut Slider)>) {
+    for (interaction, cursor, mut slider) in &mut slider_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        if let Some(position) = cursor.normalized {
+            let value = slider.min + (slider.max - slider.min) * position.x.clamp(0.0, 1.0);
+            if slider.value != value {
+                slider.value = value;
+            }
+        }
+    }
+}
+
+fn update_slider_fills(
+    slider_query: Query<(&Slider, &Children), Changed<Slider>>,
+    mut fill_query: Query<&mut Style, With<ControlFill>>,
+) {
+    for (slider, children) in &slider_query {
+        for child in children {
+            if let Ok(mut style) = fill_query.get_mut(*child) {
+                style.width = Percent(slider.fraction() * 100.0);
+            }
+        }
+    }
+}
+
+fn update_progress_fills(
+    progress_query: Query<(&ProgressBar, &Children), Changed<ProgressBar>>,
+    mut fill_query: Query<&mut Style, With<ControlFill>>,
+) {
+    for (progress, children) in &progress_query {
+        for child in children {
+            if let Ok(mut style) = fill_query.get_mut(*child) {
+                style.width = Percent(progress.0.clamp(0.0, 1.0) * 100.0);
+            }
+        }
+    }
+}
+
+fn flip_toggles(mut toggle_query: InteractionQuery<&mut Toggle>) {
+    for (interaction, mut toggle) in &mut toggle_query {
+        if matches!(interaction, Interaction::Pressed) {
+            toggle.0 = !toggle.0;
+        }
+    }
+}
+
+fn update_toggle_indicators(
+    toggle_query: Query<(&Toggle, &Children), Changed<Toggle>>,
+    mut indicator_query: Query<&mut BackgroundColor, With<ToggleIndicator>>,
+) {
+    for (toggle, children) in &toggle_query {
+        for child in children {
+            if let Ok(mut background) = indicator_query.get_mut(*child) {
+                *background = if toggle.0 { TOGGLE_ON } else { TOGGLE_OFF }.into();
+            }
+        }
+    }
+}
+
+fn open_dropdowns(
+    mut commands: Commands,
+    dropdown_query: InteractionQuery<(Entity, &Dropdown, &Children)>,
+    menu_query: Query<Entity, With<DropdownMenu>>,
+) {
+    for (interaction, (entity, dropdown, children)) in &dropdown_query {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+
+        // Pressing an open dropdown closes it again.
+        if let Some(menu) = children.iter().find(|child| menu_query.contains(**child)) {
+            commands.entity(*menu).despawn_recursive();
+            continue;
+        }
+
+        commands.entity(entity).with_children(|children| {
+            children
+                .spawn((
+                    Name::new("Dropdown Menu"),
+                    DropdownMenu,
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            top: Percent(100.0),
+                            left: Px(0.0),
+                            width: Percent(100.0),
+                            flex_direction: FlexDirection::Column,
+                            ..default()
+                        },
+                        background_color: BackgroundColor(PANEL_BACKGROUND),
+                        z_index: ZIndex::Global(90),
+                        ..default()
+                    },
+                ))
+                .with_children(|children| {
+                    for (index, option) in dropdown.options.iter().enumerate() {
+                        children
+                            .spawn((
+                                Name::new("Dropdown Option"),
+                                DropdownOption(index),
+                                ButtonBundle {
+                                    style: Style {
+                                        width: Percent(100.0),
+                                        padding: UiRect::all(Px(6.0)),
+                                        justify_content: JustifyContent::Center,
+                                        ..default()
+                                    },
+                                    background_color: BackgroundColor(NODE_BACKGROUND),
+                                    ..default()
+                                },
+                                InteractionPalette {
+                                    none: NODE_BACKGROUND,
+                                    hovered: BUTTON_HOVERED_BACKGROUND,
+                                    pressed: BUTTON_PRESSED_BACKGROUND,
+                                },
+                            ))
+                            .with_children(|children| {
+                                children.spawn(TextBundle::from_section(
+                                    option.clone(),
+                                    TextStyle {
+                                        font_size: 20.0,
+                                        color: BUTTON_TEXT,
+                                        ..default()
+                                    },
+                                ));
+                            });
+                    }
+                });
+        });
+    }
+}
+
+fn select_dropdown_options(
+    mut commands: Commands,
+    option_query: InteractionQuery<(&DropdownOption, &Parent)>,
+    menu_query: Query<&Parent, With<DropdownMenu>>,
+    mut dropdown_query: Query<&mut Dropdown>,
+) {
+    for (interaction, (option, menu)) in &option_query {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+
+        let Ok(dropdown_entity) = menu_query.get(menu.get()) else {
+            continue;
+        };
+        if let Ok(mut dropdown) = dropdown_query.get_mut(dropdown_entity.get()) {
+            dropdown.selected = option.0;
+        }
+        commands.entity(menu.get()).despawn_recursive();
+    }
+}
+
+fn update_dropdown_labels(
+    dropdown_query: Query<(&Dropdown, &Children), Changed<Dropdown>>,
+    mut label_query: Query<&mut Text, With<DropdownLabel>>,
+) {
+    for (dropdown, children) in &dropdown_query {
+        for child in children {
+            if let Ok(mut text) = label_query.get_mut(*child) {
+                text.sections[0].value = dropdown.selected_option().to_string();
+            }
+        }
+    }
+}
+
+fn scroll_lists(
+    mut mouse_wheel: EventReader<MouseWheel>,
+    mut list_query: Query<(
+        &mut ScrollList,
+        &RelativeCursorPosition,
+        &Node,
+        &Style,
+        &Children,
+    )>,
+    mut item_query: Query<(&Node, &mut Style), Without<ScrollList>>,
+) {
+    let scroll: f32 = mouse_wheel
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y * 24.0,
+            MouseScrollUnit::Pixel => event.y,
+        })
+        .sum();
+    if scroll == 0.0 {
+        return;
+    }
+
+    for (mut list, cursor, node, list_style, children) in &mut list_query {
+        if !cursor.mouse_over() {
+            continue;
+        }
+
+        let items: Vec<f32> = children
+            .iter()
+            .filter_map(|child| item_query.get(*child).ok())
+            .map(|(item, _)| item.size().y)
+            .collect();
+        // The gaps between rows take up room too.
+        let gap = match list_style.row_gap {
+            Px(gap) => gap,
+            _ => 0.0,
+        };
+        let content_height = items.iter().sum::<f32>() + gap * items.len().saturating_sub(1) as f32;
+        let max_offset = (content_height - node.size().y).max(0.0);
+        list.offset = (list.offset - scroll).clamp(0.0, max_offset);
+
+        for child in children {
+            if let Ok((_, mut style)) = item_query.get_mut(*child) {
+                style.top = Px(-list.offset);
+            }
+        }
+    }
+}
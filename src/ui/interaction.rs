@@ -4,7 +4,15 @@ use crate::game::{assets::SfxKey, audio::sfx::PlaySfx};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<InteractionPalette>();
-    app.add_systems(Update, (apply_interaction_palette, trigger_interaction_sfx));
+    app.register_type::<InteractionBorderPalette>();
+    app.add_systems(
+        Update,
+        (
+            apply_interaction_palette,
+            apply_interaction_border_palette,
+            trigger_interaction_sfx,
+        ),
+    );
 }
 
 pub type InteractionQuery<'w, 's, T> =
@@ -19,6 +27,15 @@ pub struct InteractionPalette {
     pub pressed: Color,
 }
 
+/// Palette for widget interactions that highlight their border instead of their background.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct InteractionBorderPalette {
+    pub none: Color,
+    pub hovered: Color,
+    pub pressed: Color,
+}
+
 fn apply_interaction_palette(
     mut palette_query: InteractionQuery<(&InteractionPalette, &mut BackgroundColor)>,
 ) {
@@ -32,6 +49,19 @@ fn apply_interaction_palette(
     }
 }
 
+fn apply_interaction_border_palette(
+    mut palette_query: InteractionQuery<(&InteractionBorderPalette, &mut BorderColor)>,
+) {
+    for (interaction, (palette, mut border)) in &mut palette_query {
+        *border = match interaction {
+            Interaction::None => palette.none,
+            Interaction::Hovered => palette.hovered,
+            Interaction::Pressed => palette.pressed,
+        }
+        .into();
+    }
+}
+
 fn trigger_interaction_sfx(
     mut interactions: Query<&Interaction, Changed<Interaction>>,
     mut commands: Commands,
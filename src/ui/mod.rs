@@ -3,14 +3,18 @@
 // Unused utilities and re-exports may trigger these lints undesirably.
 #![allow(dead_code, unused_imports)]
 
+pub mod controls;
 pub mod interaction;
 pub mod palette;
+pub mod tooltip;
 mod widgets;
 
 pub mod prelude {
     pub use super::{
-        interaction::{InteractionPalette, InteractionQuery},
+        controls::{Dropdown, ProgressBar, ScrollList, Slider, Toggle},
+        interaction::{InteractionBorderPalette, InteractionPalette, InteractionQuery},
         palette as ui_palette,
+        tooltip::Tooltip,
         widgets::{Containers as _, Widgets as _},
     };
 }
@@ -18,5 +22,5 @@ pub mod prelude {
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(interaction::plugin);
+    app.add_plugins((interaction::plugin, controls::plugin, tooltip::plugin));
 }
@@ -8,3 +8,21 @@ pub const LABEL_TEXT: Color = Color::srgb(0.867, 0.827, 0.412);
 pub const HEADER_TEXT: Color = Color::srgb(0.867, 0.827, 0.412);
 
 pub const NODE_BACKGROUND: Color = Color::srgb(0.286, 0.478, 0.773);
+
+pub const HUD_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+pub const READOUT_VALUE_TEXT: Color = Color::srgb(0.925, 0.925, 0.925);
+
+pub const PANEL_BACKGROUND: Color = Color::srgba(0.05, 0.07, 0.12, 0.9);
+pub const PANEL_BORDER: Color = Color::srgb(0.286, 0.478, 0.773);
+
+pub const CONTROL_TRACK: Color = Color::srgb(0.12, 0.16, 0.24);
+pub const CONTROL_FILL: Color = Color::srgb(0.867, 0.827, 0.412);
+pub const TOGGLE_OFF: Color = Color::srgb(0.12, 0.16, 0.24);
+pub const TOGGLE_ON: Color = Color::srgb(0.867, 0.827, 0.412);
+
+pub const TOOLTIP_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.85);
+pub const TOOLTIP_TEXT: Color = Color::srgb(0.925, 0.925, 0.925);
+
+pub const OVERLAY_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.75);
+
+pub const ERROR_TEXT: Color = Color::srgb(0.9, 0.3, 0.3);
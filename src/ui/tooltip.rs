@@ -0,0 +1,101 @@
+//! Hover tooltips for any interactive UI node.
+
+use bevy::{prelude::*, ui::Val::*, window::PrimaryWindow};
+
+use super::palette::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Tooltip>();
+    app.add_systems(Startup, spawn_tooltip_popup);
+    app.add_systems(Update, show_tooltips);
+}
+
+/// Text shown next to the cursor while this node is hovered.
+/// The node needs an [`Interaction`] component, e.g. from a `ButtonBundle`.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct Tooltip(pub String);
+
+impl Tooltip {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self(text.into())
+    }
+}
+
+/// The single popup that displays the text of the hovered [`Tooltip`].
+#[derive(Component)]
+struct TooltipPopup;
+
+const TOOLTIP_CURSOR_OFFSET: f32 = 16.0;
+
+fn spawn_tooltip_popup(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Tooltip"),
+            TooltipPopup,
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    max_width: Px(320.0),
+                    padding: UiRect::all(Px(6.0)),
+                    ..default()
+                },
+                background_color: BackgroundColor(TOOLTIP_BACKGROUND),
+                z_index: ZIndex::Global(100),
+                ..default()
+            },
+        ))
+        .with_children(|children| {
+            children.spawn((
+                Name::new("Tooltip Text"),
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 18.0,
+                        color: TOOLTIP_TEXT,
+                        ..default()
+                    },
+                ),
+            ));
+        });
+}
+
+fn show_tooltips(
+    tooltip_query: Query<(&Interaction, &Tooltip)>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut popup_query: Query<(&mut Style, &Children), With<TooltipPopup>>,
+    mut text_query: Query<&mut Text>,
+) {
+    let Ok((mut style, children)) = popup_query.get_single_mut() else {
+        return;
+    };
+
+    let hovered = tooltip_query
+        .iter()
+        .find(|(interaction, _)| **interaction != Interaction::None)
+        .map(|(_, tooltip)| tooltip);
+    let cursor = window_query
+        .get_single()
+        .ok()
+        .and_then(Window::cursor_position);
+
+    let (Some(tooltip), Some(cursor)) = (hovered, cursor) else {
+        if style.display != Display::None {
+            style.display = Display::None;
+        }
+        return;
+    };
+
+    style.display = Display::Flex;
+    style.left = Px(cursor.x + TOOLTIP_CURSOR_OFFSET);
+    style.top = Px(cursor.y + TOOLTIP_CURSOR_OFFSET);
+
+    for child in children {
+        if let Ok(mut text) = text_query.get_mut(*child) {
+            if text.sections[0].value != tooltip.0 {
+                text.sections[0].value.clone_from(&tooltip.0);
+            }
+        }
+    }
+}
@@ -1,19 +1,60 @@
 //! Helper traits for creating common widgets.
 
-use bevy::{ecs::system::EntityCommands, prelude::*, ui::Val::*};
+use bevy::{
+    ecs::system::EntityCommands,
+    prelude::*,
+    ui::{FocusPolicy, RelativeCursorPosition, Val::*},
+};
 
-use super::{interaction::InteractionPalette, palette::*};
+use super::{
+    controls::{
+        ControlFill, Dropdown, DropdownLabel, ProgressBar, ScrollList, Slider, Toggle,
+        ToggleIndicator,
+    },
+    interaction::{InteractionBorderPalette, InteractionPalette},
+    palette::*,
+};
 
 /// An extension trait for spawning UI widgets.
 pub trait Widgets {
     /// Spawn a simple button with text.
     fn button(&mut self, text: impl Into<String>) -> EntityCommands;
 
+    /// Spawn a button with smaller text that fits in lists and panels.
+    fn small_button(&mut self, text: impl Into<String>) -> EntityCommands;
+
     /// Spawn a simple header label. Bigger than [`Widgets::label`].
     fn header(&mut self, text: impl Into<String>) -> EntityCommands;
 
     /// Spawn a simple text label.
     fn label(&mut self, text: impl Into<String>) -> EntityCommands;
+
+    /// Spawn a small label followed by a value that can be updated through
+    /// the second section of its [`Text`].
+    fn readout(&mut self, label: impl Into<String>) -> EntityCommands;
+
+    /// Spawn a horizontal strip that lays out its children in a row.
+    fn toolbar(&mut self) -> EntityCommands;
+
+    /// Spawn a framed panel with a title. Children are laid out below the title.
+    fn panel(&mut self, title: impl Into<String>) -> EntityCommands;
+
+    /// Spawn a slider that can be dragged between `min` and `max`.
+    /// See [`Slider`].
+    fn slider(&mut self, value: f32, min: f32, max: f32) -> EntityCommands;
+
+    /// Spawn a labelled checkbox. See [`Toggle`].
+    fn toggle(&mut self, text: impl Into<String>, on: bool) -> EntityCommands;
+
+    /// Spawn a button that opens a list of options. See [`Dropdown`].
+    fn dropdown(&mut self, options: Vec<String>, selected: usize) -> EntityCommands;
+
+    /// Spawn a bar filled to `progress`, between 0 and 1. See [`ProgressBar`].
+    fn progress_bar(&mut self, progress: f32) -> EntityCommands;
+
+    /// Spawn a list of the given height that scrolls its children with the mouse wheel.
+    /// See [`ScrollList`].
+    fn scroll_list(&mut self, height: f32) -> EntityCommands;
 }
 
 impl<T: Spawn> Widgets for T {
@@ -53,6 +94,42 @@ impl<T: Spawn> Widgets for T {
         entity
     }
 
+    fn small_button(&mut self, text: impl Into<String>) -> EntityCommands {
+        let mut entity = self.spawn((
+            Name::new("Button"),
+            ButtonBundle {
+                style: Style {
+                    width: Px(300.0),
+                    height: Px(40.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: BackgroundColor(NODE_BACKGROUND),
+                ..default()
+            },
+            InteractionPalette {
+                none: NODE_BACKGROUND,
+                hovered: BUTTON_HOVERED_BACKGROUND,
+                pressed: BUTTON_PRESSED_BACKGROUND,
+            },
+        ));
+        entity.with_children(|children| {
+            children.spawn((
+                Name::new("Button Text"),
+                TextBundle::from_section(
+                    text,
+                    TextStyle {
+                        font_size: 24.0,
+                        color: BUTTON_TEXT,
+                        ..default()
+                    },
+                ),
+            ));
+        });
+        entity
+    }
+
     fn header(&mut self, text: impl Into<String>) -> EntityCommands {
         let mut entity = self.spawn((
             Name::new("Header"),
@@ -112,6 +189,272 @@ impl<T: Spawn> Widgets for T {
         });
         entity
     }
+
+    fn readout(&mut self, label: impl Into<String>) -> EntityCommands {
+        self.spawn((
+            Name::new("Readout"),
+            TextBundle::from_sections([
+                TextSection::new(
+                    format!("{}: ", label.into()),
+                    TextStyle {
+                        font_size: 20.0,
+                        color: LABEL_TEXT,
+                        ..default()
+                    },
+                ),
+                TextSection::new(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: READOUT_VALUE_TEXT,
+                        ..default()
+                    },
+                ),
+            ]),
+        ))
+    }
+
+    fn toolbar(&mut self) -> EntityCommands {
+        self.spawn((
+            Name::new("Toolbar"),
+            NodeBundle {
+                style: Style {
+                    width: Percent(100.0),
+                    padding: UiRect::axes(Px(16.0), Px(8.0)),
+                    justify_content: JustifyContent::SpaceBetween,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Px(16.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(HUD_BACKGROUND),
+                ..default()
+            },
+        ))
+    }
+
+    fn panel(&mut self, title: impl Into<String>) -> EntityCommands {
+        let mut entity = self.spawn((
+            Name::new("Panel"),
+            NodeBundle {
+                style: Style {
+                    padding: UiRect::all(Px(12.0)),
+                    border: UiRect::all(Px(2.0)),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Px(8.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(PANEL_BACKGROUND),
+                border_color: BorderColor(PANEL_BORDER),
+                ..default()
+            },
+        ));
+        entity.with_children(|children| {
+            children.spawn((
+                Name::new("Panel Title"),
+                TextBundle::from_section(
+                    title,
+                    TextStyle {
+                        font_size: 28.0,
+                        color: HEADER_TEXT,
+                        ..default()
+                    },
+                ),
+            ));
+        });
+        entity
+    }
+
+    fn slider(&mut self, value: f32, min: f32, max: f32) -> EntityCommands {
+        let slider = Slider { value, min, max };
+        let mut entity = self.spawn((
+            Name::new("Slider"),
+            ButtonBundle {
+                style: Style {
+                    width: Px(300.0),
+                    height: Px(24.0),
+                    border: UiRect::all(Px(2.0)),
+                    ..default()
+                },
+                background_color: BackgroundColor(CONTROL_TRACK),
+                border_color: BorderColor(NODE_BACKGROUND),
+                ..default()
+            },
+            InteractionBorderPalette {
+                none: NODE_BACKGROUND,
+                hovered: BUTTON_HOVERED_BACKGROUND,
+                pressed: BUTTON_PRESSED_BACKGROUND,
+            },
+            RelativeCursorPosition::default(),
+            slider,
+        ));
+        entity.with_children(|children| {
+            children.spawn((
+                Name::new("Slider Fill"),
+                ControlFill,
+                NodeBundle {
+                    style: Style {
+                        width: Percent(slider.fraction() * 100.0),
+                        height: Percent(100.0),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(CONTROL_FILL),
+                    ..default()
+                },
+            ));
+        });
+        entity
+    }
+
+    fn toggle(&mut self, text: impl Into<String>, on: bool) -> EntityCommands {
+        let mut entity = self.spawn((
+            Name::new("Toggle"),
+            ButtonBundle {
+                style: Style {
+                    width: Px(300.0),
+                    height: Px(40.0),
+                    padding: UiRect::horizontal(Px(8.0)),
+                    column_gap: Px(12.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: BackgroundColor(NODE_BACKGROUND),
+                ..default()
+            },
+            InteractionPalette {
+                none: NODE_BACKGROUND,
+                hovered: BUTTON_HOVERED_BACKGROUND,
+                pressed: BUTTON_PRESSED_BACKGROUND,
+            },
+            Toggle(on),
+        ));
+        entity.with_children(|children| {
+            children.spawn((
+                Name::new("Toggle Indicator"),
+                ToggleIndicator,
+                NodeBundle {
+                    style: Style {
+                        width: Px(20.0),
+                        height: Px(20.0),
+                        border: UiRect::all(Px(2.0)),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(if on { TOGGLE_ON } else { TOGGLE_OFF }),
+                    border_color: BorderColor(BUTTON_TEXT),
+                    ..default()
+                },
+            ));
+            children.spawn((
+                Name::new("Toggle Text"),
+                TextBundle::from_section(
+                    text,
+                    TextStyle {
+                        font_size: 24.0,
+                        color: BUTTON_TEXT,
+                        ..default()
+                    },
+                ),
+            ));
+        });
+        entity
+    }
+
+    fn dropdown(&mut self, options: Vec<String>, selected: usize) -> EntityCommands {
+        let dropdown = Dropdown { options, selected };
+        let text = dropdown.selected_option().to_string();
+        let mut entity = self.spawn((
+            Name::new("Dropdown"),
+            ButtonBundle {
+                style: Style {
+                    width: Px(300.0),
+                    height: Px(40.0),
+                    border: UiRect::all(Px(2.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: BackgroundColor(CONTROL_TRACK),
+                border_color: BorderColor(NODE_BACKGROUND),
+                ..default()
+            },
+            InteractionBorderPalette {
+                none: NODE_BACKGROUND,
+                hovered: BUTTON_HOVERED_BACKGROUND,
+                pressed: BUTTON_PRESSED_BACKGROUND,
+            },
+            dropdown,
+        ));
+        entity.with_children(|children| {
+            children.spawn((
+                Name::new("Dropdown Text"),
+                DropdownLabel,
+                TextBundle::from_section(
+                    text,
+                    TextStyle {
+                        font_size: 24.0,
+                        color: BUTTON_TEXT,
+                        ..default()
+                    },
+                ),
+            ));
+        });
+        entity
+    }
+
+    fn progress_bar(&mut self, progress: f32) -> EntityCommands {
+        let mut entity = self.spawn((
+            Name::new("Progress Bar"),
+            NodeBundle {
+                style: Style {
+                    width: Px(300.0),
+                    height: Px(16.0),
+                    border: UiRect::all(Px(2.0)),
+                    ..default()
+                },
+                background_color: BackgroundColor(CONTROL_TRACK),
+                border_color: BorderColor(NODE_BACKGROUND),
+                ..default()
+            },
+            ProgressBar(progress),
+        ));
+        entity.with_children(|children| {
+            children.spawn((
+                Name::new("Progress Fill"),
+                ControlFill,
+                NodeBundle {
+                    style: Style {
+                        width: Percent(progress.clamp(0.0, 1.0) * 100.0),
+                        height: Percent(100.0),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(CONTROL_FILL),
+                    ..default()
+                },
+            ));
+        });
+        entity
+    }
+
+    fn scroll_list(&mut self, height: f32) -> EntityCommands {
+        self.spawn((
+            Name::new("Scroll List"),
+            NodeBundle {
+                style: Style {
+                    width: Px(500.0),
+                    height: Px(height),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Px(10.0),
+                    overflow: Overflow::clip_y(),
+                    ..default()
+                },
+                ..default()
+            },
+            RelativeCursorPosition::default(),
+            ScrollList::default(),
+        ))
+    }
 }
 
 /// An extension trait for spawning UI containers.
@@ -119,6 +462,14 @@ pub trait Containers {
     /// Spawns a root node that covers the full screen
     /// and centers its content horizontally and vertically.
     fn ui_root(&mut self) -> EntityCommands;
+
+    /// Spawns a root node that covers the full screen
+    /// and stacks its content from the top edge down.
+    fn hud_root(&mut self) -> EntityCommands;
+
+    /// Spawns a root node that dims everything below it, blocks interactions
+    /// with it and centers its content horizontally and vertically.
+    fn overlay_root(&mut self) -> EntityCommands;
 }
 
 impl Containers for Commands<'_, '_> {
@@ -140,6 +491,46 @@ impl Containers for Commands<'_, '_> {
             },
         ))
     }
+
+    fn overlay_root(&mut self) -> EntityCommands {
+        self.spawn((
+            Name::new("Overlay Root"),
+            NodeBundle {
+                style: Style {
+                    width: Percent(100.0),
+                    height: Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Px(10.0),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: BackgroundColor(OVERLAY_BACKGROUND),
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(50),
+                ..default()
+            },
+        ))
+    }
+
+    fn hud_root(&mut self) -> EntityCommands {
+        self.spawn((
+            Name::new("HUD Root"),
+            NodeBundle {
+                style: Style {
+                    width: Percent(100.0),
+                    height: Percent(100.0),
+                    justify_content: JustifyContent::FlexStart,
+                    align_items: AlignItems::Stretch,
+                    flex_direction: FlexDirection::Column,
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+    }
 }
 
 /// An internal trait for types that can spawn entities.